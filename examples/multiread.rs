@@ -6,7 +6,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use gpio_cdev::{Chip, LineRequestFlags};
+use gpio_cdev::Chip;
 use quicli::prelude::*;
 use structopt::StructOpt;
 
@@ -20,11 +20,7 @@ struct Cli {
 
 fn do_main(args: Cli) -> std::result::Result<(), gpio_cdev::Error> {
     let mut chip = Chip::new(args.chip)?;
-    let ini_vals = vec![0; args.lines.len()];
-    let handle =
-        chip.get_lines(&args.lines)?
-            .request(LineRequestFlags::INPUT, &ini_vals, "multiread")?;
-    println!("Values: {:?}", handle.get_values()?);
+    println!("Values: {:?}", chip.read_lines(&args.lines, "multiread")?);
 
     Ok(())
 }
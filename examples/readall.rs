@@ -18,7 +18,7 @@ struct Cli {
 
 fn do_main(args: Cli) -> std::result::Result<(), gpio_cdev::Error> {
     let mut chip = Chip::new(args.chip)?;
-    let ini_vals = vec![0; chip.num_lines() as usize];
+    let ini_vals = vec![0; chip.num_lines_usize()];
     let handle = chip
         .get_all_lines()?
         .request(LineRequestFlags::INPUT, &ini_vals, "readall")?;
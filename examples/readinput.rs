@@ -6,23 +6,19 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use gpio_cdev::{Chip, LineRequestFlags};
+use gpio_cdev::{LineRequestFlags, LineSpec};
 use quicli::prelude::*;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 struct Cli {
-    /// The gpiochip device (e.g. /dev/gpiochip0)
-    chip: String,
-    /// The offset of the GPIO line for the provided chip
-    line: u32,
+    /// The GPIO line as <chip>:<offset> (e.g. gpiochip0:17)
+    line: LineSpec,
 }
 
 fn do_main(args: Cli) -> std::result::Result<(), gpio_cdev::Error> {
-    let mut chip = Chip::new(args.chip)?;
-    let handle = chip
-        .get_line(args.line)?
-        .request(LineRequestFlags::INPUT, 0, "readinput")?;
+    let (_chip, line) = args.line.open()?;
+    let handle = line.request(LineRequestFlags::INPUT, 0, "readinput")?;
     println!("Value: {:?}", handle.get_value()?);
 
     Ok(())
@@ -9,15 +9,20 @@
 //! Wrapper for asynchronous programming using Tokio.
 
 use futures::ready;
-use futures::stream::Stream;
+use futures::stream::{Stream, StreamExt};
 use futures::task::{Context, Poll};
 use tokio::io::unix::{AsyncFd, TryIoError};
 
-use std::os::unix::io::AsRawFd;
+use std::collections::VecDeque;
+use std::io::ErrorKind as IoErrorKind;
 use std::pin::Pin;
+use std::time::Duration;
 
-use super::event_err;
-use super::{LineEvent, LineEventHandle, Result};
+use super::{event_err, events_lost_err};
+use super::{
+    Line, LineEvent, LineEventHandle, LineHandle, LineRequestFlags, Lines, MultiLineEventHandle,
+    MultiLineHandle, RawEvent, Result,
+};
 
 /// Wrapper around a `LineEventHandle` which implements a `futures::stream::Stream` for interrupts.
 ///
@@ -53,8 +58,29 @@ use super::{LineEvent, LineEventHandle, Result};
 /// #     print_events(42).await.unwrap();
 /// # }
 /// ```
+///
+/// # Lost events
+///
+/// The kernel buffers a limited number of events per line and drops new
+/// ones if they are not read in time.  For handles requested through the
+/// v2 uAPI (see [`Line::events_with`]) such a gap in the event sequence
+/// numbers is reported by yielding an [`ErrorKind::EventsLost`] error
+/// before the next event; the stream can be polled further afterwards.
+/// Handles requested through the v1 uAPI carry no sequence numbers, so
+/// lost events cannot be detected for them.
+///
+/// To keep up with high event rates, [`with_max_per_poll`] allows
+/// reading several events from the kernel each time the line becomes
+/// readable.
+///
+/// [`Line::events_with`]: struct.Line.html#method.events_with
+/// [`ErrorKind::EventsLost`]: errors/enum.ErrorKind.html#variant.EventsLost
+/// [`with_max_per_poll`]: struct.AsyncLineEventHandle.html#method.with_max_per_poll
 pub struct AsyncLineEventHandle {
     asyncfd: AsyncFd<LineEventHandle>,
+    max_per_poll: usize,
+    buffer: VecDeque<Result<LineEvent>>,
+    last_seqno: Option<u32>,
 }
 
 impl AsyncLineEventHandle {
@@ -65,30 +91,140 @@ impl AsyncLineEventHandle {
     /// * `handle` - handle to be wrapped.
     pub fn new(handle: LineEventHandle) -> Result<AsyncLineEventHandle> {
         // The file descriptor needs to be configured for non-blocking I/O for PollEvented to work.
-        let fd = handle.file.as_raw_fd();
-        unsafe {
-            let flags = libc::fcntl(fd, libc::F_GETFL, 0);
-            libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
-        }
+        handle.set_nonblocking(true)?;
 
         Ok(AsyncLineEventHandle {
             asyncfd: AsyncFd::new(handle)?,
+            max_per_poll: 1,
+            buffer: VecDeque::new(),
+            last_seqno: None,
         })
     }
+
+    /// Read up to `max` events from the kernel whenever the line is readable
+    ///
+    /// By default a single event is read per wakeup.  With a larger batch,
+    /// events that are already queued in the kernel are moved into a buffer
+    /// of this handle in one go, which makes it less likely for the kernel
+    /// queue to overflow while the consumer is busy.  Values of `0` are
+    /// treated as `1`.
+    pub fn with_max_per_poll(mut self, max: usize) -> Self {
+        self.max_per_poll = max.max(1);
+        self
+    }
+
+    /// Queue an event read from the kernel, preceded by an error if the
+    /// sequence numbers show that events were dropped in between.
+    fn push_event(
+        buffer: &mut VecDeque<Result<LineEvent>>,
+        last_seqno: &mut Option<u32>,
+        event: RawEvent,
+    ) {
+        if let Some(seqno) = event.seqno {
+            if let Some(last) = *last_seqno {
+                let lost = seqno.wrapping_sub(last).wrapping_sub(1);
+                if lost != 0 {
+                    buffer.push_back(Err(events_lost_err(lost)));
+                }
+            }
+            *last_seqno = Some(seqno);
+        }
+        buffer.push_back(event.checked());
+    }
+
+    /// Request the current state of the line from the kernel
+    ///
+    /// This is the same as [`LineEventHandle::get_value`].  Reading the
+    /// value is a single ioctl which the kernel services immediately
+    /// without waiting on the hardware, so it is fine to call from an
+    /// async task.
+    ///
+    /// [`LineEventHandle::get_value`]: struct.LineEventHandle.html#method.get_value
+    pub fn get_value(&self) -> Result<u8> {
+        self.asyncfd.get_ref().get_value()
+    }
+
+    /// Wait for the next event, giving up after `timeout`
+    ///
+    /// Returns `Ok(None)` if no event occurred in time.  This is the same
+    /// as wrapping `next()` in `tokio::time::timeout`, but keeps the error
+    /// type of this crate.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use gpio_cdev::{AsyncLineEventHandle, Chip, EventRequestFlags, LineRequestFlags};
+    /// use std::time::Duration;
+    ///
+    /// async fn wait_for_press() -> Result<(), gpio_cdev::Error> {
+    ///     let mut chip = Chip::new("/dev/gpiochip0")?;
+    ///     let line = chip.get_line(4)?;
+    ///     let mut events = AsyncLineEventHandle::new(line.events(
+    ///         LineRequestFlags::INPUT,
+    ///         EventRequestFlags::FALLING_EDGE,
+    ///         "button",
+    ///     )?)?;
+    ///
+    ///     match events.next_timeout(Duration::from_secs(5)).await? {
+    ///         Some(event) => println!("Pressed at {}", event.timestamp()),
+    ///         None => println!("Timed out"),
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #     wait_for_press().await.unwrap();
+    /// # }
+    /// ```
+    pub async fn next_timeout(&mut self, timeout: Duration) -> Result<Option<LineEvent>> {
+        match tokio::time::timeout(timeout, self.next()).await {
+            Ok(Some(event)) => event.map(Some),
+            Ok(None) | Err(_) => Ok(None),
+        }
+    }
 }
 
 impl Stream for AsyncLineEventHandle {
     type Item = Result<LineEvent>;
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
         loop {
-            let mut guard = ready!(self.asyncfd.poll_read_ready_mut(cx))?;
-            match guard.try_io(|inner| inner.get_mut().read_event()) {
+            if let Some(item) = this.buffer.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+
+            let mut guard = ready!(this.asyncfd.poll_read_ready_mut(cx))?;
+            let buffer = &mut this.buffer;
+            let last_seqno = &mut this.last_seqno;
+            let max_per_poll = this.max_per_poll;
+            let res = guard.try_io(|inner| {
+                let handle = inner.get_mut();
+                for _ in 0..max_per_poll {
+                    match handle.read_event() {
+                        Ok(Some(event)) => {
+                            AsyncLineEventHandle::push_event(buffer, last_seqno, event)
+                        }
+                        Ok(None) => {
+                            buffer.push_back(Err(event_err(nix::errno::Errno::EIO)));
+                            break;
+                        }
+                        // Deliver what was read so far, readiness is cleared on the
+                        // next attempt
+                        Err(err) if err.kind() == IoErrorKind::WouldBlock && !buffer.is_empty() => {
+                            break
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+                Ok(())
+            });
+            match res {
                 Err(TryIoError { .. }) => {
                     // Continue
                 }
-                Ok(Ok(Some(event))) => return Poll::Ready(Some(Ok(event))),
-                Ok(Ok(None)) => return Poll::Ready(Some(Err(event_err(nix::errno::Errno::EIO)))),
+                Ok(Ok(())) => {}
                 Ok(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
             }
         }
@@ -100,3 +236,257 @@ impl AsRef<LineEventHandle> for AsyncLineEventHandle {
         self.asyncfd.get_ref()
     }
 }
+
+/// Wrapper around several `LineEventHandle`s which merges their events into a single
+/// `futures::stream::Stream`.
+///
+/// Each item is paired with the offset of the line which generated the event.
+/// The lines are polled in turn so that a busy line cannot starve the others.
+///
+/// # Example
+///
+/// The following example prints the events of several input lines.
+///
+/// ```no_run
+/// use futures::stream::StreamExt;
+/// use gpio_cdev::{AsyncMultiLineEventHandle, Chip, EventRequestFlags, LineRequestFlags};
+///
+/// async fn print_events(offsets: &[u32]) -> Result<(), gpio_cdev::Error> {
+///     let mut chip = Chip::new("/dev/gpiochip0")?;
+///     let mut handles = Vec::new();
+///     for &offset in offsets {
+///         handles.push(chip.get_line(offset)?.events(
+///             LineRequestFlags::INPUT,
+///             EventRequestFlags::BOTH_EDGES,
+///             "gpioevents",
+///         )?);
+///     }
+///     let mut events = AsyncMultiLineEventHandle::new(handles)?;
+///
+///     while let Some(event) = events.next().await {
+///         let (offset, event) = event?;
+///         println!("[{}] {:?}", offset, event);
+///     }
+///
+///     Ok(())
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// #     print_events(&[4, 5, 6]).await.unwrap();
+/// # }
+/// ```
+///
+/// With the v2 uAPI, [`from_request`] instead waits on a single file
+/// descriptor shared by all lines, see [`Lines::events`].
+///
+/// [`from_request`]: struct.AsyncMultiLineEventHandle.html#method.from_request
+/// [`Lines::events`]: struct.Lines.html#method.events
+pub struct AsyncMultiLineEventHandle {
+    source: Source,
+}
+
+enum Source {
+    /// One request per line, polled in turn
+    Handles {
+        handles: Vec<AsyncLineEventHandle>,
+        next: usize,
+    },
+    /// A single v2 request for all lines
+    Request {
+        asyncfd: AsyncFd<MultiLineEventHandle>,
+        buffer: VecDeque<Result<LineEvent>>,
+        last_seqno: Option<u32>,
+    },
+}
+
+impl AsyncMultiLineEventHandle {
+    /// Wraps the specified `LineEventHandle`s.
+    ///
+    /// # Arguments
+    ///
+    /// * `handles` - handles to be wrapped.
+    pub fn new<I>(handles: I) -> Result<AsyncMultiLineEventHandle>
+    where
+        I: IntoIterator<Item = LineEventHandle>,
+    {
+        let handles = handles
+            .into_iter()
+            .map(AsyncLineEventHandle::new)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AsyncMultiLineEventHandle {
+            source: Source::Handles { handles, next: 0 },
+        })
+    }
+
+    /// Wraps the specified `MultiLineEventHandle`.
+    ///
+    /// Lost events are reported as [`ErrorKind::EventsLost`] errors, as for
+    /// [`AsyncLineEventHandle`].
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - handle to be wrapped.
+    ///
+    /// [`ErrorKind::EventsLost`]: errors/enum.ErrorKind.html#variant.EventsLost
+    /// [`AsyncLineEventHandle`]: struct.AsyncLineEventHandle.html
+    pub fn from_request(handle: MultiLineEventHandle) -> Result<AsyncMultiLineEventHandle> {
+        // The file descriptor needs to be configured for non-blocking I/O for PollEvented to work.
+        handle.set_nonblocking(true)?;
+
+        Ok(AsyncMultiLineEventHandle {
+            source: Source::Request {
+                asyncfd: AsyncFd::new(handle)?,
+                buffer: VecDeque::new(),
+                last_seqno: None,
+            },
+        })
+    }
+
+    /// The wrapped handles, in the order they were provided
+    ///
+    /// This is empty for handles created with [`from_request`].
+    ///
+    /// [`from_request`]: struct.AsyncMultiLineEventHandle.html#method.from_request
+    pub fn handles(&self) -> &[AsyncLineEventHandle] {
+        match &self.source {
+            Source::Handles { handles, .. } => handles,
+            Source::Request { .. } => &[],
+        }
+    }
+}
+
+impl Stream for AsyncMultiLineEventHandle {
+    type Item = Result<(u32, LineEvent)>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        match &mut self.get_mut().source {
+            Source::Handles { handles, next } => {
+                let n = handles.len();
+                let mut finished = 0;
+                for i in 0..n {
+                    let idx = (*next + i) % n;
+                    let handle = &mut handles[idx];
+                    let offset = handle.as_ref().line().offset();
+                    match Pin::new(handle).poll_next(cx) {
+                        Poll::Ready(Some(res)) => {
+                            // Start with the following line next time around
+                            *next = (idx + 1) % n;
+                            return Poll::Ready(Some(res.map(|event| (offset, event))));
+                        }
+                        Poll::Ready(None) => finished += 1,
+                        Poll::Pending => {}
+                    }
+                }
+
+                if finished == n {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                }
+            }
+            Source::Request {
+                asyncfd,
+                buffer,
+                last_seqno,
+            } => loop {
+                if let Some(item) = buffer.pop_front() {
+                    return Poll::Ready(Some(item.map(|event| {
+                        let offset = event.offset().unwrap_or_default();
+                        (offset, event)
+                    })));
+                }
+
+                let mut guard = ready!(asyncfd.poll_read_ready_mut(cx))?;
+                match guard.try_io(|inner| inner.get_mut().read_event()) {
+                    Err(TryIoError { .. }) => {
+                        // Continue
+                    }
+                    Ok(Ok(Some(event))) => {
+                        AsyncLineEventHandle::push_event(buffer, last_seqno, event)
+                    }
+                    Ok(Ok(None)) => {
+                        return Poll::Ready(Some(Err(event_err(nix::errno::Errno::EIO))))
+                    }
+                    Ok(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                }
+            },
+        }
+    }
+}
+
+impl Line {
+    /// Request this line without blocking the async executor
+    ///
+    /// This is [`Line::request`] run on tokio's thread pool for blocking
+    /// operations (`tokio::task::spawn_blocking`), so it must be called from
+    /// within a tokio runtime.
+    ///
+    /// [`Line::request`]: struct.Line.html#method.request
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-tokio")))]
+    pub async fn request_async(
+        &self,
+        flags: LineRequestFlags,
+        default: u8,
+        consumer: &str,
+    ) -> Result<LineHandle> {
+        let line = self.clone();
+        let consumer = consumer.to_owned();
+        spawn_blocking(move || line.request(flags, default, &consumer)).await
+    }
+}
+
+impl Lines {
+    /// Request these lines without blocking the async executor
+    ///
+    /// This is [`Lines::request`] run on tokio's thread pool for blocking
+    /// operations (`tokio::task::spawn_blocking`), so it must be called from
+    /// within a tokio runtime.
+    ///
+    /// [`Lines::request`]: struct.Lines.html#method.request
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-tokio")))]
+    pub async fn request_async(
+        &self,
+        flags: LineRequestFlags,
+        default: &[u8],
+        consumer: &str,
+    ) -> Result<MultiLineHandle> {
+        let lines = Lines {
+            lines: self.lines.clone(),
+        };
+        let default = default.to_vec();
+        let consumer = consumer.to_owned();
+        spawn_blocking(move || lines.request(flags, &default, &consumer)).await
+    }
+}
+
+/// Run `f` with `tokio::task::spawn_blocking`, passing on panics
+async fn spawn_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        // The task was cancelled because the runtime is shutting down
+        Err(_) => Err(std::io::Error::from_raw_os_error(libc::ECANCELED).into()),
+    }
+}
+
+impl LineHandle {
+    /// Drive the line through a sequence of values without blocking
+    ///
+    /// This is the asynchronous version of [`drive_pattern`], waiting with
+    /// `tokio::time::sleep` between values.
+    ///
+    /// [`drive_pattern`]: struct.LineHandle.html#method.drive_pattern
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-tokio")))]
+    pub async fn drive_pattern_async(&self, pattern: &[(u8, Duration)]) -> Result<()> {
+        for &(value, duration) in pattern {
+            self.set_value(value)?;
+            tokio::time::sleep(duration).await;
+        }
+        Ok(())
+    }
+}
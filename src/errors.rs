@@ -2,25 +2,63 @@
 //!
 //! In futures versions of the crate, this module will no longer be included in the crate.
 
-use crate::IoctlKind;
+use crate::ffi::{GPIOHANDLES_MAX, GPIO_V2_LINE_NUM_ATTRS_MAX};
+use crate::{IoctlKind, LineRequestFlags};
 use std::error::Error as StdError;
 use std::fmt;
 use std::io::Error as IOError;
+use std::path::{Path, PathBuf};
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Error {
     kind: ErrorKind,
 }
 
+/// The different kinds of errors reported by this crate
+///
+/// `ErrorKind` implements `PartialEq` to make errors easy to check for in
+/// tests.  The `Io` variant compares equal if the wrapped errors have the
+/// same [`std::io::ErrorKind`]; all other variants compare all of their
+/// fields.
 #[derive(Debug)]
 pub enum ErrorKind {
     Event(nix::Error),
     Io(IOError),
-    Ioctl { kind: IoctlKind, cause: nix::Error },
+    Ioctl {
+        kind: IoctlKind,
+        cause: nix::Error,
+    },
     InvalidRequest(usize, usize),
     Offset(u32),
+    TooManyLines(usize),
+    NotAChip(PathBuf),
+    ConflictingFlags(LineRequestFlags),
+    UnknownEvent(u32),
+    Index(usize, usize),
+    NameNotFound(String),
+    RequiresV2,
+    EventsLost(u32),
+    DefaultsNotApplied {
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
+    NameMismatch {
+        offset: u32,
+        expected: String,
+        actual: Option<String>,
+    },
+    InvalidValue(u8),
+    InvalidLineSpec(String),
+    InvalidConsumer(Vec<u8>),
+    TooManyConfigs(usize),
+    NoEdges,
+    PortWidth(usize),
+    NotAnInput(u32),
+    Busy {
+        consumer: Option<String>,
+    },
 }
 
 pub(crate) fn ioctl_err(kind: IoctlKind, cause: nix::Error) -> Error {
@@ -35,18 +73,232 @@ pub(crate) fn invalid_err(n_lines: usize, n_values: usize) -> Error {
     }
 }
 
+pub(crate) fn invalid_value_err(value: u8) -> Error {
+    Error {
+        kind: ErrorKind::InvalidValue(value),
+    }
+}
+
+pub(crate) fn line_spec_err(spec: &str) -> Error {
+    Error {
+        kind: ErrorKind::InvalidLineSpec(spec.to_owned()),
+    }
+}
+
+pub(crate) fn invalid_consumer_err(consumer: &[u8]) -> Error {
+    Error {
+        kind: ErrorKind::InvalidConsumer(consumer.to_vec()),
+    }
+}
+
 pub(crate) fn offset_err(offset: u32) -> Error {
     Error {
         kind: ErrorKind::Offset(offset),
     }
 }
 
+pub(crate) fn too_many_lines_err(n_lines: usize) -> Error {
+    Error {
+        kind: ErrorKind::TooManyLines(n_lines),
+    }
+}
+
+pub(crate) fn too_many_configs_err(n_configs: usize) -> Error {
+    Error {
+        kind: ErrorKind::TooManyConfigs(n_configs),
+    }
+}
+
+pub(crate) fn no_edges_err() -> Error {
+    Error {
+        kind: ErrorKind::NoEdges,
+    }
+}
+
+pub(crate) fn port_width_err(n_lines: usize) -> Error {
+    Error {
+        kind: ErrorKind::PortWidth(n_lines),
+    }
+}
+
+pub(crate) fn not_an_input_err(offset: u32) -> Error {
+    Error {
+        kind: ErrorKind::NotAnInput(offset),
+    }
+}
+
+pub(crate) fn busy_err(consumer: Option<String>) -> Error {
+    Error {
+        kind: ErrorKind::Busy { consumer },
+    }
+}
+
+pub(crate) fn not_a_chip_err(path: &Path) -> Error {
+    Error {
+        kind: ErrorKind::NotAChip(path.to_path_buf()),
+    }
+}
+
+pub(crate) fn conflicting_flags_err(flags: LineRequestFlags) -> Error {
+    Error {
+        kind: ErrorKind::ConflictingFlags(flags),
+    }
+}
+
+pub(crate) fn unknown_event_err(id: u32) -> Error {
+    Error {
+        kind: ErrorKind::UnknownEvent(id),
+    }
+}
+
+pub(crate) fn index_err(index: usize, n_lines: usize) -> Error {
+    Error {
+        kind: ErrorKind::Index(index, n_lines),
+    }
+}
+
+pub(crate) fn name_not_found_err(name: &str) -> Error {
+    Error {
+        kind: ErrorKind::NameNotFound(name.to_owned()),
+    }
+}
+
+pub(crate) fn requires_v2_err() -> Error {
+    Error {
+        kind: ErrorKind::RequiresV2,
+    }
+}
+
+pub(crate) fn defaults_not_applied_err(expected: &[u8], actual: Vec<u8>) -> Error {
+    Error {
+        kind: ErrorKind::DefaultsNotApplied {
+            expected: expected.to_vec(),
+            actual,
+        },
+    }
+}
+
+pub(crate) fn name_mismatch_err(offset: u32, expected: &str, actual: Option<&str>) -> Error {
+    Error {
+        kind: ErrorKind::NameMismatch {
+            offset,
+            expected: expected.to_owned(),
+            actual: actual.map(str::to_owned),
+        },
+    }
+}
+
+pub(crate) fn events_lost_err(n_events: u32) -> Error {
+    Error {
+        kind: ErrorKind::EventsLost(n_events),
+    }
+}
+
 pub(crate) fn event_err(err: nix::Error) -> Error {
     Error {
         kind: ErrorKind::Event(err),
     }
 }
 
+impl PartialEq for ErrorKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ErrorKind::Event(a), ErrorKind::Event(b)) => a == b,
+            (ErrorKind::Io(a), ErrorKind::Io(b)) => a.kind() == b.kind(),
+            (ErrorKind::Ioctl { kind: a, cause: ac }, ErrorKind::Ioctl { kind: b, cause: bc }) => {
+                a == b && ac == bc
+            }
+            (ErrorKind::InvalidRequest(al, av), ErrorKind::InvalidRequest(bl, bv)) => {
+                al == bl && av == bv
+            }
+            (ErrorKind::Offset(a), ErrorKind::Offset(b)) => a == b,
+            (ErrorKind::InvalidValue(a), ErrorKind::InvalidValue(b)) => a == b,
+            (ErrorKind::InvalidLineSpec(a), ErrorKind::InvalidLineSpec(b)) => a == b,
+            (ErrorKind::InvalidConsumer(a), ErrorKind::InvalidConsumer(b)) => a == b,
+            (ErrorKind::TooManyLines(a), ErrorKind::TooManyLines(b)) => a == b,
+            (ErrorKind::TooManyConfigs(a), ErrorKind::TooManyConfigs(b)) => a == b,
+            (ErrorKind::Busy { consumer: a }, ErrorKind::Busy { consumer: b }) => a == b,
+            (ErrorKind::NoEdges, ErrorKind::NoEdges) => true,
+            (ErrorKind::PortWidth(a), ErrorKind::PortWidth(b)) => a == b,
+            (ErrorKind::NotAnInput(a), ErrorKind::NotAnInput(b)) => a == b,
+            (ErrorKind::NotAChip(a), ErrorKind::NotAChip(b)) => a == b,
+            (ErrorKind::ConflictingFlags(a), ErrorKind::ConflictingFlags(b)) => a == b,
+            (ErrorKind::UnknownEvent(a), ErrorKind::UnknownEvent(b)) => a == b,
+            (ErrorKind::Index(ai, al), ErrorKind::Index(bi, bl)) => ai == bi && al == bl,
+            (ErrorKind::NameNotFound(a), ErrorKind::NameNotFound(b)) => a == b,
+            (ErrorKind::RequiresV2, ErrorKind::RequiresV2) => true,
+            (ErrorKind::EventsLost(a), ErrorKind::EventsLost(b)) => a == b,
+            (
+                ErrorKind::DefaultsNotApplied {
+                    expected: ae,
+                    actual: aa,
+                },
+                ErrorKind::DefaultsNotApplied {
+                    expected: be,
+                    actual: ba,
+                },
+            ) => ae == be && aa == ba,
+            (
+                ErrorKind::NameMismatch {
+                    offset: ao,
+                    expected: ae,
+                    actual: aa,
+                },
+                ErrorKind::NameMismatch {
+                    offset: bo,
+                    expected: be,
+                    actual: ba,
+                },
+            ) => ao == bo && ae == be && aa == ba,
+            _ => false,
+        }
+    }
+}
+
+impl Error {
+    /// The kind of error that occurred
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// The OS error code (errno) behind this error, if any
+    ///
+    /// Available for errors reported by an ioctl, by reading an event or by
+    /// other I/O.  This allows e.g. telling a line which is busy (`EBUSY`)
+    /// apart from an invalid request (`EINVAL`):
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineRequestFlags};
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// match chip.get_line(4)?.request(LineRequestFlags::OUTPUT, 0, "example") {
+    ///     Ok(_handle) => println!("got the line"),
+    ///     Err(e) if e.raw_os_error() == Some(libc::EBUSY) => println!("busy, retry later"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match &self.kind {
+            ErrorKind::Event(err) => Some(*err as i32),
+            ErrorKind::Io(err) => err.raw_os_error(),
+            ErrorKind::Ioctl { cause, .. } => Some(*cause as i32),
+            ErrorKind::Busy { .. } => Some(libc::EBUSY),
+            _ => None,
+        }
+    }
+
+    /// The errno reported by the kernel if this error came from an ioctl
+    pub(crate) fn ioctl_errno(&self) -> Option<nix::errno::Errno> {
+        match self.kind {
+            ErrorKind::Ioctl { cause, .. } => Some(cause),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for IoctlKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -56,6 +308,8 @@ impl fmt::Display for IoctlKind {
             IoctlKind::LineEvent => write!(f, "get line event "),
             IoctlKind::GetLine => write!(f, "get line value"),
             IoctlKind::SetLine => write!(f, "set line value"),
+            IoctlKind::LineRequest => write!(f, "request line"),
+            IoctlKind::SetConfig => write!(f, "set line config"),
         }
     }
 }
@@ -66,12 +320,100 @@ impl fmt::Display for Error {
             ErrorKind::Event(err) => write!(f, "Failed to read event: {}", err),
             ErrorKind::Io(err) => err.fmt(f),
             ErrorKind::Ioctl { cause, kind } => write!(f, "Ioctl to {} failed: {}", kind, cause),
+            ErrorKind::InvalidRequest(0, 0) => write!(f, "Invalid request: no lines given"),
             ErrorKind::InvalidRequest(n_lines, n_values) => write!(
                 f,
                 "Invalid request: {} values requested to be set but only {} lines are open",
                 n_values, n_lines
             ),
             ErrorKind::Offset(offset) => write!(f, "Offset {} is out of range", offset),
+            ErrorKind::InvalidValue(value) => {
+                write!(f, "Invalid line value {}: must be 0 or 1", value)
+            }
+            ErrorKind::InvalidLineSpec(spec) => write!(
+                f,
+                "Invalid line \"{}\": expected <chip>:<offset>, e.g. gpiochip0:17",
+                spec
+            ),
+            ErrorKind::InvalidConsumer(consumer) => write!(
+                f,
+                "Invalid consumer label {:?}: labels cannot contain NUL bytes",
+                String::from_utf8_lossy(consumer)
+            ),
+            ErrorKind::TooManyLines(n_lines) => write!(
+                f,
+                "Too many lines: {} lines requested but at most {} can be requested at once; \
+                 request the lines in batches",
+                n_lines, GPIOHANDLES_MAX
+            ),
+            ErrorKind::TooManyConfigs(n_configs) => write!(
+                f,
+                "Too many line configurations: {} requested but a request can hold at most {}",
+                n_configs, GPIO_V2_LINE_NUM_ATTRS_MAX
+            ),
+            ErrorKind::Busy {
+                consumer: Some(consumer),
+            } => write!(f, "Line is busy, held by \"{}\"", consumer),
+            ErrorKind::Busy { consumer: None } => write!(f, "Line is busy"),
+            ErrorKind::NoEdges => write!(
+                f,
+                "No edge requested: events need RISING_EDGE, FALLING_EDGE or BOTH_EDGES"
+            ),
+            ErrorKind::NotAnInput(offset) => write!(
+                f,
+                "Line {} is not requested as an input, which edge detection requires",
+                offset
+            ),
+            ErrorKind::PortWidth(n_lines) => write!(
+                f,
+                "Invalid port width: a parallel port has 1 to 8 lines but {} were given",
+                n_lines
+            ),
+            ErrorKind::NotAChip(path) => {
+                write!(f, "{} is not a GPIO character device", path.display())
+            }
+            ErrorKind::ConflictingFlags(flags) => {
+                write!(f, "Conflicting line request flags: {:?}", flags)
+            }
+            ErrorKind::UnknownEvent(id) => write!(f, "Unknown event id {} read from kernel", id),
+            ErrorKind::Index(index, n_lines) => write!(
+                f,
+                "Index {} is out of range for a handle with {} lines",
+                index, n_lines
+            ),
+            ErrorKind::NameNotFound(name) => write!(f, "No line named \"{}\" found", name),
+            ErrorKind::RequiresV2 => write!(
+                f,
+                "Operation requires a line requested through the GPIO uAPI v2"
+            ),
+            ErrorKind::EventsLost(n_events) => write!(
+                f,
+                "{} events were lost because the kernel event queue overflowed",
+                n_events
+            ),
+            ErrorKind::DefaultsNotApplied { expected, actual } => write!(
+                f,
+                "Lines read back as {:?} after being requested with default values {:?}",
+                actual, expected
+            ),
+            ErrorKind::NameMismatch {
+                offset,
+                expected,
+                actual: Some(actual),
+            } => write!(
+                f,
+                "Line {} is named \"{}\" instead of \"{}\"",
+                offset, actual, expected
+            ),
+            ErrorKind::NameMismatch {
+                offset,
+                expected,
+                actual: None,
+            } => write!(
+                f,
+                "Line {} has no name but \"{}\" was expected",
+                offset, expected
+            ),
         }
     }
 }
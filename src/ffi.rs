@@ -6,7 +6,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+//! Raw definitions of the kernel GPIO character device uAPI (`linux/gpio.h`).
+
+// Not everything is used by the safe API; the rest is for users of the `raw` module.
+#![cfg_attr(not(feature = "raw-ffi"), allow(dead_code))]
+
 use crate::IoctlKind;
+use nix::sys::ioctl::ioctl_num_type;
+use std::mem::size_of;
 
 pub const GPIOHANDLES_MAX: usize = 64;
 
@@ -50,19 +57,118 @@ pub struct gpioevent_request {
     pub fd: libc::c_int,
 }
 
+pub const GPIOEVENT_EVENT_RISING_EDGE: u32 = 0x01;
+pub const GPIOEVENT_EVENT_FALLING_EDGE: u32 = 0x02;
+
 #[repr(C)]
 pub struct gpioevent_data {
     pub timestamp: u64,
     pub id: u32,
 }
 
+// uAPI v2 (Linux 5.10+)
+
+pub const GPIO_V2_LINES_MAX: usize = 64;
+pub const GPIO_V2_LINE_NUM_ATTRS_MAX: usize = 10;
+
+// enum gpio_v2_line_flag
+pub const GPIO_V2_LINE_FLAG_USED: u64 = 1 << 0;
+pub const GPIO_V2_LINE_FLAG_ACTIVE_LOW: u64 = 1 << 1;
+pub const GPIO_V2_LINE_FLAG_INPUT: u64 = 1 << 2;
+pub const GPIO_V2_LINE_FLAG_OUTPUT: u64 = 1 << 3;
+pub const GPIO_V2_LINE_FLAG_EDGE_RISING: u64 = 1 << 4;
+pub const GPIO_V2_LINE_FLAG_EDGE_FALLING: u64 = 1 << 5;
+pub const GPIO_V2_LINE_FLAG_OPEN_DRAIN: u64 = 1 << 6;
+pub const GPIO_V2_LINE_FLAG_OPEN_SOURCE: u64 = 1 << 7;
+pub const GPIO_V2_LINE_FLAG_BIAS_PULL_UP: u64 = 1 << 8;
+pub const GPIO_V2_LINE_FLAG_BIAS_PULL_DOWN: u64 = 1 << 9;
+pub const GPIO_V2_LINE_FLAG_BIAS_DISABLED: u64 = 1 << 10;
+pub const GPIO_V2_LINE_FLAG_EVENT_CLOCK_REALTIME: u64 = 1 << 11;
+pub const GPIO_V2_LINE_FLAG_EVENT_CLOCK_HTE: u64 = 1 << 12;
+
+// enum gpio_v2_line_attr_id
+pub const GPIO_V2_LINE_ATTR_ID_FLAGS: u32 = 1;
+pub const GPIO_V2_LINE_ATTR_ID_OUTPUT_VALUES: u32 = 2;
+pub const GPIO_V2_LINE_ATTR_ID_DEBOUNCE: u32 = 3;
+
+// enum gpio_v2_line_event_id
+pub const GPIO_V2_LINE_EVENT_RISING_EDGE: u32 = 1;
+pub const GPIO_V2_LINE_EVENT_FALLING_EDGE: u32 = 2;
+
+#[repr(C)]
+pub struct gpio_v2_line_values {
+    pub bits: u64,
+    pub mask: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union gpio_v2_line_attribute_value {
+    pub flags: u64,
+    pub values: u64,
+    pub debounce_period_us: u32,
+}
+
+#[repr(C)]
+pub struct gpio_v2_line_attribute {
+    pub id: u32,
+    pub padding: u32,
+    pub value: gpio_v2_line_attribute_value,
+}
+
+#[repr(C)]
+pub struct gpio_v2_line_config_attribute {
+    pub attr: gpio_v2_line_attribute,
+    pub mask: u64,
+}
+
+#[repr(C)]
+pub struct gpio_v2_line_config {
+    pub flags: u64,
+    pub num_attrs: u32,
+    pub padding: [u32; 5],
+    pub attrs: [gpio_v2_line_config_attribute; GPIO_V2_LINE_NUM_ATTRS_MAX],
+}
+
+#[repr(C)]
+pub struct gpio_v2_line_request {
+    pub offsets: [u32; GPIO_V2_LINES_MAX],
+    pub consumer: [libc::c_char; 32],
+    pub config: gpio_v2_line_config,
+    pub num_lines: u32,
+    pub event_buffer_size: u32,
+    pub padding: [u32; 5],
+    pub fd: i32,
+}
+
+#[repr(C)]
+pub struct gpio_v2_line_info {
+    pub name: [libc::c_char; 32],
+    pub consumer: [libc::c_char; 32],
+    pub offset: u32,
+    pub num_attrs: u32,
+    pub flags: u64,
+    pub attrs: [gpio_v2_line_attribute; GPIO_V2_LINE_NUM_ATTRS_MAX],
+    pub padding: [u32; 4],
+}
+
+#[repr(C)]
+pub struct gpio_v2_line_event {
+    pub timestamp_ns: u64,
+    pub id: u32,
+    pub offset: u32,
+    pub seqno: u32,
+    pub line_seqno: u32,
+    pub padding: [u32; 6],
+}
+
 macro_rules! wrap_ioctl {
     ($ioctl_macro:ident!($name:ident, $ioty:expr, $nr:expr, $ty:ident), $ioctl_error_type:expr) => {
         mod $name {
             $ioctl_macro!($name, $ioty, $nr, super::$ty);
         }
 
-        pub(crate) fn $name(fd: libc::c_int, data: &mut $ty) -> crate::errors::Result<libc::c_int> {
+        pub fn $name(fd: libc::c_int, data: &mut $ty) -> crate::errors::Result<libc::c_int> {
             unsafe {
                 $name::$name(fd, data).map_err(|e| crate::errors::ioctl_err($ioctl_error_type, e))
             }
@@ -105,3 +211,63 @@ wrap_ioctl!(
     ),
     IoctlKind::SetLine
 );
+
+wrap_ioctl!(
+    ioctl_readwrite!(gpio_v2_get_lineinfo_ioctl, 0xB4, 0x05, gpio_v2_line_info),
+    IoctlKind::LineInfo
+);
+wrap_ioctl!(
+    ioctl_readwrite!(gpio_v2_get_line_ioctl, 0xB4, 0x07, gpio_v2_line_request),
+    IoctlKind::LineRequest
+);
+wrap_ioctl!(
+    ioctl_readwrite!(
+        gpio_v2_line_set_config_ioctl,
+        0xB4,
+        0x0D,
+        gpio_v2_line_config
+    ),
+    IoctlKind::SetConfig
+);
+wrap_ioctl!(
+    ioctl_readwrite!(
+        gpio_v2_line_get_values_ioctl,
+        0xB4,
+        0x0E,
+        gpio_v2_line_values
+    ),
+    IoctlKind::GetLine
+);
+wrap_ioctl!(
+    ioctl_readwrite!(
+        gpio_v2_line_set_values_ioctl,
+        0xB4,
+        0x0F,
+        gpio_v2_line_values
+    ),
+    IoctlKind::SetLine
+);
+
+// ioctl request codes, for use with other ioctl wrappers
+pub const GPIO_GET_CHIPINFO_IOCTL: ioctl_num_type =
+    request_code_read!(0xB4, 0x01, size_of::<gpiochip_info>());
+pub const GPIO_GET_LINEINFO_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x02, size_of::<gpioline_info>());
+pub const GPIO_GET_LINEHANDLE_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x03, size_of::<gpiohandle_request>());
+pub const GPIO_GET_LINEEVENT_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x04, size_of::<gpioevent_request>());
+pub const GPIOHANDLE_GET_LINE_VALUES_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x08, size_of::<gpiohandle_data>());
+pub const GPIOHANDLE_SET_LINE_VALUES_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x09, size_of::<gpiohandle_data>());
+pub const GPIO_V2_GET_LINEINFO_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x05, size_of::<gpio_v2_line_info>());
+pub const GPIO_V2_GET_LINE_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x07, size_of::<gpio_v2_line_request>());
+pub const GPIO_V2_LINE_SET_CONFIG_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x0D, size_of::<gpio_v2_line_config>());
+pub const GPIO_V2_LINE_GET_VALUES_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x0E, size_of::<gpio_v2_line_values>());
+pub const GPIO_V2_LINE_SET_VALUES_IOCTL: ioctl_num_type =
+    request_code_readwrite!(0xB4, 0x0F, size_of::<gpio_v2_line_values>());
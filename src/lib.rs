@@ -80,6 +80,13 @@
 //! # Ok(()) }
 //! ```
 //!
+//! # Threads
+//!
+//! Chips, lines and all handles are `Send` and `Sync`: they only hold file
+//! descriptors and reference counted chip state.  A handle can thus be
+//! moved to another thread, and a `Chip` shared through an `Arc` (or simply
+//! cloned, which shares the file descriptor).
+//!
 //! [README on Github]: https://github.com/rust-embedded/rust-gpio-cdev
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -90,24 +97,55 @@ extern crate bitflags;
 extern crate nix;
 
 use std::cmp::min;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::ffi::CStr;
-use std::fs::{read_dir, File, ReadDir};
+use std::fs::{read_dir, File, OpenOptions, ReadDir};
 use std::io::Read;
+use std::iter::FusedIterator;
 use std::mem;
 use std::ops::Index;
-use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, RawFd};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, RawFd};
 use std::path::{Path, PathBuf};
 use std::ptr;
 use std::slice;
-use std::sync::Arc;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "async-tokio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async-tokio")))]
 mod async_tokio;
 pub mod errors; // pub portion is deprecated
 mod ffi;
+#[cfg(feature = "mio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mio")))]
+mod mio_source;
+#[cfg(feature = "mock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+pub mod mock;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Raw access to the kernel GPIO uAPI
+///
+/// This exposes the `#[repr(C)]` structures, constants and ioctl wrappers
+/// of both the v1 and v2 uAPI from `linux/gpio.h` as used internally by
+/// this crate.  It is an escape hatch for functionality the safe API does
+/// not cover yet; all of it is `unsafe` to get wrong and none of it is
+/// covered by semver guarantees, so it may change in any release.
+///
+/// The ioctl wrappers take the raw file descriptor of a [`Chip`] or of a
+/// handle (see `AsRawFd`).
+///
+/// [`Chip`]: ../struct.Chip.html
+#[cfg(feature = "raw-ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "raw-ffi")))]
+pub mod raw {
+    pub use crate::ffi::*;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum IoctlKind {
     ChipInfo,
     LineInfo,
@@ -115,26 +153,123 @@ pub enum IoctlKind {
     LineEvent,
     GetLine,
     SetLine,
+    LineRequest,
+    SetConfig,
 }
 
 #[cfg(feature = "async-tokio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async-tokio")))]
-pub use crate::async_tokio::AsyncLineEventHandle;
+pub use crate::async_tokio::{AsyncLineEventHandle, AsyncMultiLineEventHandle};
 pub use errors::*;
 
-unsafe fn rstr_lcpy(dst: *mut libc::c_char, src: &str, length: usize) {
-    let copylen = min(src.len() + 1, length);
-    ptr::copy_nonoverlapping(src.as_bytes().as_ptr().cast(), dst, copylen - 1);
-    slice::from_raw_parts_mut(dst, length)[copylen - 1] = 0;
+// Keep the promise of the "Threads" section of the crate documentation
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Chip>();
+    assert_send_sync::<ChipInfo>();
+    assert_send_sync::<Line>();
+    assert_send_sync::<Lines>();
+    assert_send_sync::<LineInfo>();
+    assert_send_sync::<LineHandle>();
+    assert_send_sync::<InputLineHandle>();
+    assert_send_sync::<OutputLineHandle>();
+    assert_send_sync::<MultiLineHandle>();
+    assert_send_sync::<LineEventHandle>();
+    assert_send_sync::<MultiLineEventHandle>();
+    assert_send_sync::<MultiLineMonitor>();
+    assert_send_sync::<LineEvent>();
+    assert_send_sync::<Error>();
+};
+
+/// Copy `src` into the C string buffer `dst` of `length` bytes
+///
+/// The bytes are truncated to fit and always NUL terminated.
+unsafe fn bytes_lcpy(dst: *mut libc::c_char, src: &[u8], length: usize) {
+    let copylen = min(src.len(), length - 1);
+    ptr::copy_nonoverlapping(src.as_ptr().cast(), dst, copylen);
+    slice::from_raw_parts_mut(dst, length)[copylen] = 0;
+}
+
+/// The longest prefix of `src` which fits into a C string buffer of
+/// `length` bytes without splitting a multibyte UTF-8 character
+fn str_prefix(src: &str, length: usize) -> &[u8] {
+    let mut copylen = min(src.len(), length - 1);
+    while !src.is_char_boundary(copylen) {
+        copylen -= 1;
+    }
+    &src.as_bytes()[..copylen]
+}
+
+/// Size of the consumer label buffers of the uAPI, including the NUL
+const CONSUMER_SIZE: usize = 32;
+
+/// Reject consumer labels which the kernel would cut short at a NUL byte
+fn validate_consumer(consumer: &[u8]) -> Result<()> {
+    if consumer.contains(&0) {
+        return Err(invalid_consumer_err(consumer));
+    }
+    Ok(())
+}
+
+/// The bytes of `consumer` to store as the label of a request
+///
+/// The label is checked with `validate_consumer()` and truncated to fit
+/// the uAPI buffers without splitting a multibyte UTF-8 character.
+fn consumer_label(consumer: &str) -> Result<&[u8]> {
+    validate_consumer(consumer.as_bytes())?;
+    Ok(str_prefix(consumer, CONSUMER_SIZE))
 }
 
 #[derive(Debug)]
 struct InnerChip {
     pub path: PathBuf,
+    pub canonical_path: PathBuf,
     pub file: File,
     pub name: String,
     pub label: String,
     pub lines: u32,
+    pub device_number: (u32, u32),
+    /// Cached result of probing for the v2 uAPI, see `supports_v2()`
+    pub v2: AtomicU8,
+    /// Consumer label set with `Chip::set_consumer()`
+    pub consumer: Mutex<String>,
+}
+
+const V2_UNKNOWN: u8 = 0;
+const V2_UNSUPPORTED: u8 = 1;
+const V2_SUPPORTED: u8 = 2;
+
+impl InnerChip {
+    fn consumer(&self) -> MutexGuard<'_, String> {
+        // A panic while holding the lock cannot leave the label inconsistent
+        self.consumer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn supports_v2(&self) -> bool {
+        match self.v2.load(Ordering::Relaxed) {
+            V2_SUPPORTED => return true,
+            V2_UNSUPPORTED => return false,
+            _ => {}
+        }
+
+        // Any answer other than ENOTTY (including EINVAL for a chip without
+        // lines) means the kernel understood the v2 ioctl.
+        let mut line_info: ffi::gpio_v2_line_info = unsafe { mem::zeroed() };
+        let supported = match ffi::gpio_v2_get_lineinfo_ioctl(self.file.as_raw_fd(), &mut line_info)
+        {
+            Err(e) => e.ioctl_errno() != Some(nix::errno::Errno::ENOTTY),
+            Ok(_) => true,
+        };
+        self.v2.store(
+            if supported {
+                V2_SUPPORTED
+            } else {
+                V2_UNSUPPORTED
+            },
+            Ordering::Relaxed,
+        );
+        supported
+    }
 }
 
 /// A GPIO Chip maps to the actual device driver instance in hardware that
@@ -159,15 +294,28 @@ struct InnerChip {
 ///    is discouraged for production.
 ///
 /// [`chips()`]: fn.chips.html
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Chip {
     inner: Arc<InnerChip>,
 }
 
+/// Chips are equal if they refer to the same device, even if they were
+/// opened through different paths (e.g. a udev symlink).
+impl PartialEq for Chip {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.canonical_path == other.inner.canonical_path
+            && self.inner.name == other.inner.name
+    }
+}
+
+impl Eq for Chip {}
+
 /// Iterator over chips
 #[derive(Debug)]
 pub struct ChipIterator {
     readdir: ReadDir,
+    seen: HashSet<PathBuf>,
+    sysfs: bool,
 }
 
 impl Iterator for ChipIterator {
@@ -177,13 +325,19 @@ impl Iterator for ChipIterator {
         for entry in &mut self.readdir {
             match entry {
                 Ok(entry) => {
-                    if entry
-                        .path()
-                        .as_path()
-                        .to_string_lossy()
-                        .contains("gpiochip")
-                    {
-                        return Some(Chip::new(entry.path()));
+                    if !entry.file_name().to_string_lossy().starts_with("gpiochip") {
+                        continue;
+                    }
+                    let path = if self.sysfs {
+                        sysfs_dev_node(&entry.path(), &entry.file_name())
+                    } else {
+                        entry.path()
+                    };
+                    // Symlinks (e.g. created by udev rules) resolve to a device
+                    // node we may already have returned.
+                    let path = std::fs::canonicalize(&path).unwrap_or(path);
+                    if self.seen.insert(path.clone()) {
+                        return Some(Chip::new(path));
                     }
                 }
                 Err(e) => {
@@ -197,23 +351,167 @@ impl Iterator for ChipIterator {
 }
 
 /// Iterate over all GPIO chips currently present on this system
+///
+/// Each chip is only returned once, even if it can be reached through
+/// several entries in `/dev` (such as symlinks), and opened through its
+/// resolved path.
 pub fn chips() -> Result<ChipIterator> {
     Ok(ChipIterator {
         readdir: read_dir("/dev")?,
+        seen: HashSet::new(),
+        sysfs: false,
+    })
+}
+
+/// Open all GPIO chips currently present on this system
+///
+/// This collects [`chips`] into the chips which could be opened and the
+/// errors for those which could not (e.g. because of their permissions),
+/// so that one inaccessible chip does not prevent using the others.  If
+/// `/dev` cannot be read at all, that error is the only one returned.
+///
+/// [`chips`]: fn.chips.html
+pub fn chips_collect() -> (Vec<Chip>, Vec<Error>) {
+    let mut opened = Vec::new();
+    let mut errors = Vec::new();
+    match chips() {
+        Ok(iter) => {
+            for chip in iter {
+                match chip {
+                    Ok(chip) => opened.push(chip),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+        Err(e) => errors.push(e),
+    }
+    (opened, errors)
+}
+
+/// Find a line by its name on any GPIO chip of this system
+///
+/// The chips are searched in the order returned by [`chips`], and the
+/// first line with the given name is returned along with its chip.  Line
+/// names are set by the device tree or ACPI tables and are often more
+/// meaningful than offsets, which differ between board variants.
+///
+/// Returns `Ok(None)` if no line has that name.  Errors opening a chip or
+/// reading the info of a line are returned.
+///
+/// ```no_run
+/// # fn main() -> Result<(), gpio_cdev::Error> {
+/// use gpio_cdev::LineRequestFlags;
+///
+/// if let Some((_chip, line)) = gpio_cdev::find_line("USER_LED")? {
+///     let led = line.request(LineRequestFlags::OUTPUT, 1, "example")?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`chips`]: fn.chips.html
+pub fn find_line(name: &str) -> Result<Option<(Chip, Line)>> {
+    for chip in chips()? {
+        let chip = chip?;
+        if let Some(line) = chip.find_line(name)? {
+            return Ok(Some((chip, line)));
+        }
+    }
+    Ok(None)
+}
+
+/// Iterate over all GPIO chips registered with the kernel, as listed in sysfs
+///
+/// Unlike [`chips`], which scans `/dev`, this enumerates the chips in
+/// `/sys/bus/gpio/devices` and opens each through its device node.  The
+/// node is looked up by device number in `/dev/char` (maintained by udev),
+/// falling back to `/dev/gpiochipN`, so this also finds chips whose nodes
+/// have been renamed.
+///
+/// [`chips`]: fn.chips.html
+pub fn chips_from_sysfs() -> Result<ChipIterator> {
+    Ok(ChipIterator {
+        readdir: read_dir("/sys/bus/gpio/devices")?,
+        seen: HashSet::new(),
+        sysfs: true,
     })
 }
 
+/// Find the device node of the chip at `sysfs_path` in sysfs
+fn sysfs_dev_node(sysfs_path: &Path, name: &std::ffi::OsStr) -> PathBuf {
+    if let Ok(dev) = std::fs::read_to_string(sysfs_path.join("dev")) {
+        let node = Path::new("/dev/char").join(dev.trim());
+        if node.exists() {
+            return node;
+        }
+    }
+    Path::new("/dev").join(name)
+}
+
 impl Chip {
     /// Open the GPIO Chip at the provided path (e.g. `/dev/gpiochip<N>`)
+    ///
+    /// If the path exists but does not refer to a GPIO character device
+    /// (e.g. `/dev/null` or a regular file), an [`ErrorKind::NotAChip`]
+    /// error is returned.
+    ///
+    /// The chip, and every line handle requested from it, is opened with
+    /// `O_CLOEXEC`, so the file descriptors are not inherited by child
+    /// processes started with `exec`.
+    ///
+    /// The chip is opened read-only: none of the ioctls need write access,
+    /// not even those requesting lines which are then driven as outputs.
+    /// Read permission on the device node is therefore enough to list and
+    /// use the lines of the chip.
+    ///
+    /// [`ErrorKind::NotAChip`]: errors/enum.ErrorKind.html#variant.NotAChip
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let f = File::open(path.as_ref())?;
+        Self::open_with(path, OpenOptions::new().read(true))
+    }
+
+    /// Open the GPIO Chip at the provided path with custom options
+    ///
+    /// This is like [`new`], which opens the chip read-only, but allows
+    /// passing additional flags such as `O_NONBLOCK` through
+    /// `std::os::unix::fs::OpenOptionsExt`.  The standard library always
+    /// adds `O_CLOEXEC`, and the kernel sets it for the file descriptors of
+    /// requested lines, so none of them leak into child processes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::Chip;
+    /// use std::fs::OpenOptions;
+    /// use std::os::unix::fs::OpenOptionsExt;
+    ///
+    /// let mut options = OpenOptions::new();
+    /// options.read(true).custom_flags(libc::O_CLOEXEC);
+    /// let chip = Chip::open_with("/dev/gpiochip0", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`new`]: struct.Chip.html#method.new
+    pub fn open_with<P: AsRef<Path>>(path: P, options: &OpenOptions) -> Result<Self> {
+        let f = options.open(path.as_ref())?;
+        let rdev = f.metadata()?.rdev();
         let mut info: ffi::gpiochip_info = unsafe { mem::zeroed() };
-        ffi::gpio_get_chipinfo_ioctl(f.as_raw_fd(), &mut info)?;
+        ffi::gpio_get_chipinfo_ioctl(f.as_raw_fd(), &mut info).map_err(|e| {
+            match e.ioctl_errno() {
+                // The chip info ioctl is the first GPIO specific call we make, so a
+                // device which does not understand it is not a gpiochip at all.
+                Some(nix::errno::Errno::ENOTTY) => not_a_chip_err(path.as_ref()),
+                _ => e,
+            }
+        })?;
 
         Ok(Self {
             inner: Arc::new(InnerChip {
                 file: f,
                 path: path.as_ref().to_path_buf(),
+                canonical_path: std::fs::canonicalize(path.as_ref())
+                    .unwrap_or_else(|_| path.as_ref().to_path_buf()),
                 name: unsafe {
                     CStr::from_ptr(info.name.as_ptr())
                         .to_string_lossy()
@@ -225,10 +523,26 @@ impl Chip {
                         .into_owned()
                 },
                 lines: info.lines,
+                device_number: (
+                    libc::major(rdev as libc::dev_t) as u32,
+                    libc::minor(rdev as libc::dev_t) as u32,
+                ),
+                v2: AtomicU8::new(V2_UNKNOWN),
+                consumer: Mutex::new(String::new()),
             }),
         })
     }
 
+    /// Get another handle to this chip
+    ///
+    /// This is the same as `clone()`: both handles share the underlying
+    /// file descriptor, so this is cheap and, unlike opening the device
+    /// again, does not create a new file descriptor.  Chips are `Send` and
+    /// `Sync`, so the handle can be passed to other threads.
+    pub fn try_clone(&self) -> Chip {
+        self.clone()
+    }
+
     /// Get the fs path of this character device (e.g. `/dev/gpiochipN`)
     pub fn path(&self) -> &Path {
         self.inner.path.as_path()
@@ -239,6 +553,15 @@ impl Chip {
         self.inner.name.as_str()
     }
 
+    /// A stable identifier for this GPIO chip
+    ///
+    /// This is the kernel name of the chip (e.g. `gpiochip0`), which is
+    /// unique among the chips present on the system and does not depend
+    /// on the path used to open the chip.
+    pub fn id(&self) -> &str {
+        self.name()
+    }
+
     /// A functional name for this GPIO chip, such as a product number.  Might
     /// be an empty string.
     ///
@@ -247,6 +570,45 @@ impl Chip {
         self.inner.label.as_str()
     }
 
+    /// Set the consumer label used by [`Line::request_default_consumer`]
+    ///
+    /// This allows labelling all requests of a program the same way
+    /// without repeating the label.  The label is shared with all clones of
+    /// this chip and the lines obtained from it, including those obtained
+    /// before the label was set.  It is empty initially, which leaves
+    /// requested lines without a label.
+    ///
+    /// [`Line::request_default_consumer`]: struct.Line.html#method.request_default_consumer
+    pub fn set_consumer(&mut self, consumer: &str) {
+        *self.inner.consumer() = consumer.to_owned();
+    }
+
+    /// The consumer label set with [`set_consumer`]
+    ///
+    /// [`set_consumer`]: struct.Chip.html#method.set_consumer
+    pub fn consumer(&self) -> String {
+        self.inner.consumer().clone()
+    }
+
+    /// Get a snapshot of the information about this chip
+    ///
+    /// This bundles what the individual accessors of `Chip` report, which
+    /// is convenient for tools reporting the capabilities of a controller.
+    /// Determining v2 support may involve a probe of the kernel, see
+    /// [`supports_v2`].
+    ///
+    /// [`supports_v2`]: struct.Chip.html#method.supports_v2
+    pub fn info(&self) -> ChipInfo {
+        ChipInfo {
+            path: self.inner.path.clone(),
+            name: self.inner.name.clone(),
+            label: self.inner.label.clone(),
+            lines: self.inner.lines,
+            device_number: self.inner.device_number,
+            supports_v2: self.supports_v2(),
+        }
+    }
+
     /// The number of lines/pins indexable through this chip
     ///
     /// Not all of these may be usable depending on how the hardware is
@@ -255,6 +617,55 @@ impl Chip {
         self.inner.lines
     }
 
+    /// Get the number of lines on the chip as a `usize`
+    ///
+    /// This is the same as [`num_lines`], for sizing buffers and
+    /// indexing without a cast.
+    ///
+    /// [`num_lines`]: struct.Chip.html#method.num_lines
+    pub fn num_lines_usize(&self) -> usize {
+        self.inner.lines as usize
+    }
+
+    /// The major and minor device number of the chip
+    ///
+    /// This is read when the chip is opened.  It can be used to find the
+    /// chip in sysfs under `/sys/dev/char/<major>:<minor>`, e.g. to read
+    /// attributes not available through the character device.  As opening
+    /// the chip already checks that it is a device, this cannot fail and
+    /// returns the numbers directly rather than a `Result`.
+    pub fn device_number(&self) -> (u32, u32) {
+        self.inner.device_number
+    }
+
+    /// Whether the kernel supports the v2 GPIO character device uAPI
+    ///
+    /// The first call probes the kernel with a cheap v2 ioctl; the result
+    /// is cached and shared by all clones of this chip, so later calls are
+    /// free.  Methods such as [`Line::events_with()`] require the v2 uAPI,
+    /// which was added in Linux 5.10.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::Chip;
+    ///
+    /// let chip = Chip::new("/dev/gpiochip0")?;
+    /// if chip.supports_v2() {
+    ///     println!("using the v2 uAPI");
+    /// } else {
+    ///     println!("falling back to the v1 uAPI");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Line::events_with()`]: struct.Line.html#method.events_with
+    pub fn supports_v2(&self) -> bool {
+        self.inner.supports_v2()
+    }
+
     /// Get a handle to the GPIO line at a given offset
     ///
     /// The actual physical line corresponding to a given offset
@@ -269,21 +680,313 @@ impl Chip {
         Line::new(self.inner.clone(), offset)
     }
 
+    /// Get a handle to the GPIO line at a given offset, checking its name
+    ///
+    /// This is like [`get_line`], but additionally queries the line info
+    /// and fails with an [`ErrorKind::NameMismatch`] error unless the
+    /// kernel's name for the line is `expected_name`.  This guards against
+    /// changes of the board or device tree silently remapping lines.
+    ///
+    /// [`get_line`]: struct.Chip.html#method.get_line
+    /// [`ErrorKind::NameMismatch`]: errors/enum.ErrorKind.html#variant.NameMismatch
+    pub fn get_line_checked(&mut self, offset: u32, expected_name: &str) -> Result<Line> {
+        let line = self.get_line(offset)?;
+        let info = line.info()?;
+        if info.name() != Some(expected_name) {
+            return Err(name_mismatch_err(offset, expected_name, info.name()));
+        }
+        Ok(line)
+    }
+
     /// Get a handle to multiple GPIO line at a given offsets
     ///
     /// The group of lines can be manipulated simultaneously.
+    ///
+    /// # Errors
+    ///
+    /// At most 64 lines can be requested together; asking for more
+    /// results in an [`ErrorKind::TooManyLines`] error.
+    ///
+    /// [`ErrorKind::TooManyLines`]: errors/enum.ErrorKind.html#variant.TooManyLines
     pub fn get_lines(&mut self, offsets: &[u32]) -> Result<Lines> {
         Lines::new(self.inner.clone(), offsets)
     }
 
+    /// Get a handle to `count` consecutive lines starting at `start`
+    ///
+    /// This is [`get_lines`] for the offsets `start..start + count`, e.g.
+    /// for a parallel bus wired to consecutive pins.
+    ///
+    /// # Errors
+    ///
+    /// Ranges extending beyond the last line of the chip result in an
+    /// [`ErrorKind::Offset`] error for the first offset out of range (or
+    /// `start` if the range does not even fit a `u32`), more than 64 lines
+    /// in an [`ErrorKind::TooManyLines`] error, and a `count` of 0 in an
+    /// [`ErrorKind::InvalidRequest`] error.
+    ///
+    /// [`get_lines`]: struct.Chip.html#method.get_lines
+    /// [`ErrorKind::Offset`]: errors/enum.ErrorKind.html#variant.Offset
+    /// [`ErrorKind::TooManyLines`]: errors/enum.ErrorKind.html#variant.TooManyLines
+    /// [`ErrorKind::InvalidRequest`]: errors/enum.ErrorKind.html#variant.InvalidRequest
+    pub fn get_line_range(&mut self, start: u32, count: u32) -> Result<Lines> {
+        if count == 0 {
+            return Err(invalid_err(0, 0));
+        }
+        if count as usize > ffi::GPIOHANDLES_MAX {
+            return Err(too_many_lines_err(count as usize));
+        }
+        let end = start.checked_add(count).ok_or_else(|| offset_err(start))?;
+        let offsets: Vec<u32> = (start..end).collect();
+        self.get_lines(&offsets)
+    }
+
     /// Get a handle to all the GPIO lines on the chip
     ///
     /// The group of lines can be manipulated simultaneously.
+    ///
+    /// # Errors
+    ///
+    /// Chips with more than 64 lines cannot be requested in one go and
+    /// result in an [`ErrorKind::TooManyLines`] error.  For those chips
+    /// use [`get_lines`] to request the lines in batches instead.
+    ///
+    /// [`ErrorKind::TooManyLines`]: errors/enum.ErrorKind.html#variant.TooManyLines
+    /// [`get_lines`]: struct.Chip.html#method.get_lines
     pub fn get_all_lines(&mut self) -> Result<Lines> {
         let offsets: Vec<u32> = (0..self.num_lines()).collect();
         self.get_lines(&offsets)
     }
 
+    /// Get all the GPIO lines on the chip, split into groups of at most `chunk` lines
+    ///
+    /// This makes chips with more lines than can be requested at once
+    /// (64) usable: each group can be requested with [`Lines::request`].
+    /// Groups hold consecutive offsets, in order.
+    ///
+    /// The groups are returned unrequested, as with [`get_lines`], since
+    /// requesting them needs flags and a consumer label; use
+    /// [`request_lines_chunked`] to get them requested right away.
+    ///
+    /// # Errors
+    ///
+    /// A `chunk` of more than 64 results in an [`ErrorKind::TooManyLines`]
+    /// error, and a `chunk` of 0 in an [`ErrorKind::InvalidRequest`] error.
+    ///
+    /// [`Lines::request`]: struct.Lines.html#method.request
+    /// [`get_lines`]: struct.Chip.html#method.get_lines
+    /// [`request_lines_chunked`]: struct.Chip.html#method.request_lines_chunked
+    /// [`ErrorKind::TooManyLines`]: errors/enum.ErrorKind.html#variant.TooManyLines
+    /// [`ErrorKind::InvalidRequest`]: errors/enum.ErrorKind.html#variant.InvalidRequest
+    pub fn get_lines_chunked(&mut self, chunk: usize) -> Result<Vec<Lines>> {
+        self.chunks(chunk)
+    }
+
+    /// Request all the GPIO lines on the chip in groups of at most `chunk` lines
+    ///
+    /// This is [`get_lines_chunked`] with each group requested with
+    /// [`Lines::request`] using `flags`, `default` as the default value of
+    /// every line and `consumer`.  The handles hold consecutive offsets, in
+    /// order.  If requesting a group fails, the groups requested before are
+    /// released again.
+    ///
+    /// # Errors
+    ///
+    /// The errors are those of [`get_lines_chunked`] and [`Lines::request`].
+    ///
+    /// [`get_lines_chunked`]: struct.Chip.html#method.get_lines_chunked
+    /// [`Lines::request`]: struct.Lines.html#method.request
+    pub fn request_lines_chunked(
+        &self,
+        chunk: usize,
+        flags: LineRequestFlags,
+        default: u8,
+        consumer: &str,
+    ) -> Result<Vec<MultiLineHandle>> {
+        self.chunks(chunk)?
+            .iter()
+            .map(|lines| {
+                let defaults = vec![default; lines.len()];
+                lines.request(flags.clone(), &defaults, consumer)
+            })
+            .collect()
+    }
+
+    /// Split all lines of the chip into groups of at most `chunk` lines
+    fn chunks(&self, chunk: usize) -> Result<Vec<Lines>> {
+        if chunk == 0 {
+            return Err(invalid_err(0, 0));
+        }
+        let offsets: Vec<u32> = (0..self.num_lines()).collect();
+        offsets
+            .chunks(chunk)
+            .map(|offsets| Lines::new(self.inner.clone(), offsets))
+            .collect()
+    }
+
+    /// Read the values of all lines on the chip
+    ///
+    /// The lines are requested as inputs in groups of 64 (see
+    /// [`request_lines_chunked`]), read, and released again.  The values
+    /// are returned in offset order.
+    ///
+    /// [`request_lines_chunked`]: struct.Chip.html#method.request_lines_chunked
+    pub fn read_all_values(&mut self, consumer: &str) -> Result<Vec<u8>> {
+        let mut values = Vec::with_capacity(self.num_lines_usize());
+        for handle in
+            self.request_lines_chunked(ffi::GPIOHANDLES_MAX, LineRequestFlags::INPUT, 0, consumer)?
+        {
+            values.extend(handle.get_values()?);
+        }
+        Ok(values)
+    }
+
+    /// Read the value of a single line once
+    ///
+    /// The line at `offset` is requested as an input, read and released
+    /// again.  This is handy for one-off diagnostics; code accessing a
+    /// line repeatedly should keep a [`LineHandle`] from [`Line::request`]
+    /// instead of paying for a request each time.
+    ///
+    /// [`LineHandle`]: struct.LineHandle.html
+    /// [`Line::request`]: struct.Line.html#method.request
+    pub fn read_line(&mut self, offset: u32, consumer: &str) -> Result<u8> {
+        let handle = self
+            .get_line(offset)?
+            .request(LineRequestFlags::INPUT, 0, consumer)?;
+        let value = handle.get_value()?;
+        handle.release()?;
+        Ok(value)
+    }
+
+    /// Read the values of several lines once
+    ///
+    /// The lines at `offsets` are requested together as inputs, read and
+    /// released again.  The values are returned in the order of `offsets`.
+    /// As with [`read_line`], code reading the lines repeatedly should keep
+    /// a [`MultiLineHandle`] from [`Lines::request`] instead.
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::Chip;
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// println!("Values: {:?}", chip.read_lines(&[4, 5, 6], "multiread")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`read_line`]: struct.Chip.html#method.read_line
+    /// [`MultiLineHandle`]: struct.MultiLineHandle.html
+    /// [`Lines::request`]: struct.Lines.html#method.request
+    pub fn read_lines(&mut self, offsets: &[u32], consumer: &str) -> Result<Vec<u8>> {
+        let defaults = vec![0; offsets.len()];
+        self.get_lines(offsets)?
+            .request(LineRequestFlags::INPUT, &defaults, consumer)?
+            .get_values()
+    }
+
+    /// Drive a single line to a value once
+    ///
+    /// The line at `offset` is requested as an output with `value` as its
+    /// default and released again.  Whether the line keeps its value once
+    /// released depends on the driver, so for anything but one-off
+    /// diagnostics keep a [`LineHandle`] from [`Line::request`] for as
+    /// long as the value matters.
+    ///
+    /// [`LineHandle`]: struct.LineHandle.html
+    /// [`Line::request`]: struct.Line.html#method.request
+    pub fn write_line(&mut self, offset: u32, value: u8, consumer: &str) -> Result<()> {
+        self.get_line(offset)?
+            .request(LineRequestFlags::OUTPUT, value, consumer)?
+            .release()
+    }
+
+    /// Find the line with the given name and request it
+    ///
+    /// Looks up the first line whose [`LineInfo::name`] is `name` and then
+    /// requests it as with [`Line::request`].  This is convenient when the
+    /// board description (e.g. the device tree) gives meaningful names to
+    /// the lines, such as `"GPIO-RESET"`.
+    ///
+    /// # Errors
+    ///
+    /// If no line of this chip has the name, an [`ErrorKind::NameNotFound`]
+    /// error is returned.  Otherwise errors are as for [`Line::request`].
+    ///
+    /// [`LineInfo::name`]: struct.LineInfo.html#method.name
+    /// [`Line::request`]: struct.Line.html#method.request
+    /// [`ErrorKind::NameNotFound`]: errors/enum.ErrorKind.html#variant.NameNotFound
+    pub fn request_line_by_name(
+        &mut self,
+        name: &str,
+        flags: LineRequestFlags,
+        default: u8,
+        consumer: &str,
+    ) -> Result<LineHandle> {
+        match self.find_line(name)? {
+            Some(line) => line.request(flags, default, consumer),
+            None => Err(name_not_found_err(name)),
+        }
+    }
+
+    /// Get the info of all lines which are currently in use
+    ///
+    /// Queries the info of every line of the chip and keeps those which
+    /// are requested, by the kernel or from userspace (see
+    /// [`LineInfo::is_used`]), in order of their offsets.
+    ///
+    /// [`LineInfo::is_used`]: struct.LineInfo.html#method.is_used
+    pub fn used_lines(&self) -> Result<Vec<LineInfo>> {
+        let mut used = Vec::new();
+        for line in self.lines() {
+            let info = line.info()?;
+            if info.is_used() {
+                used.push(info);
+            }
+        }
+        Ok(used)
+    }
+
+    /// Get the names of all lines of the chip
+    ///
+    /// Returns each offset together with the name of its line (see
+    /// [`LineInfo::name`]), in offset order.  Unnamed lines have a name of
+    /// `None`.  The kernel offers no batched query, so this still queries
+    /// the info of each line in turn.
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::Chip;
+    ///
+    /// let chip = Chip::new("/dev/gpiochip0")?;
+    /// for (offset, name) in chip.line_names()? {
+    ///     println!("{}: {}", offset, name.as_deref().unwrap_or("unnamed"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`LineInfo::name`]: struct.LineInfo.html#method.name
+    pub fn line_names(&self) -> Result<Vec<(u32, Option<String>)>> {
+        self.lines()
+            .map(|line| {
+                let info = line.info()?;
+                Ok((line.offset(), info.name().map(str::to_owned)))
+            })
+            .collect()
+    }
+
+    /// Find the first line with the given name
+    fn find_line(&self, name: &str) -> Result<Option<Line>> {
+        for line in self.lines() {
+            if line.info()?.name() == Some(name) {
+                return Ok(Some(line));
+            }
+        }
+        Ok(None)
+    }
+
     /// Get an interator over all lines that can be potentially access for this
     /// chip.
     pub fn lines(&self) -> LineIterator {
@@ -294,6 +997,64 @@ impl Chip {
     }
 }
 
+/// Information about a chip
+///
+/// Obtained from [`Chip::info`].  Further information may be added as the
+/// kernel exposes it.
+///
+/// [`Chip::info`]: struct.Chip.html#method.info
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipInfo {
+    path: PathBuf,
+    name: String,
+    label: String,
+    lines: u32,
+    device_number: (u32, u32),
+    supports_v2: bool,
+}
+
+impl ChipInfo {
+    /// The fs path the chip was opened through, see [`Chip::path`]
+    ///
+    /// [`Chip::path`]: struct.Chip.html#method.path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The name of the device driving the chip, see [`Chip::name`]
+    ///
+    /// [`Chip::name`]: struct.Chip.html#method.name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The functional name of the chip, see [`Chip::label`]
+    ///
+    /// [`Chip::label`]: struct.Chip.html#method.label
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The number of lines of the chip
+    pub fn num_lines(&self) -> u32 {
+        self.lines
+    }
+
+    /// The major and minor device number, see [`Chip::device_number`]
+    ///
+    /// [`Chip::device_number`]: struct.Chip.html#method.device_number
+    pub fn device_number(&self) -> (u32, u32) {
+        self.device_number
+    }
+
+    /// Whether the kernel supports the v2 uAPI, see [`Chip::supports_v2`]
+    ///
+    /// [`Chip::supports_v2`]: struct.Chip.html#method.supports_v2
+    pub fn supports_v2(&self) -> bool {
+        self.supports_v2
+    }
+}
+
 /// Iterator over GPIO Lines for a given chip.
 #[derive(Debug)]
 pub struct LineIterator {
@@ -314,7 +1075,89 @@ impl Iterator for LineIterator {
             None
         }
     }
-}
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.chip.lines - self.idx) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for LineIterator {}
+
+impl FusedIterator for LineIterator {}
+
+/// A line given by its chip and offset, as typed on a command line
+///
+/// Parses specifiers of the form `<chip>:<offset>`, where `<chip>` is either
+/// the path of the chip device (`/dev/gpiochip0:17`) or just its name
+/// (`gpiochip0:17`), which is looked up in `/dev`.
+///
+/// ```no_run
+/// # fn main() -> Result<(), gpio_cdev::Error> {
+/// use gpio_cdev::{LineRequestFlags, LineSpec};
+///
+/// let spec: LineSpec = "gpiochip0:17".parse()?;
+/// let (_chip, line) = spec.open()?;
+/// let handle = line.request(LineRequestFlags::INPUT, 0, "example")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineSpec {
+    chip: PathBuf,
+    offset: u32,
+}
+
+impl LineSpec {
+    /// Create a specifier for the line at `offset` of the chip at `chip`
+    pub fn new<P: AsRef<Path>>(chip: P, offset: u32) -> Self {
+        Self {
+            chip: chip.as_ref().to_path_buf(),
+            offset,
+        }
+    }
+
+    /// The path of the chip device
+    pub fn chip_path(&self) -> &Path {
+        &self.chip
+    }
+
+    /// The offset of the line within its chip
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Open the chip and get the line
+    pub fn open(&self) -> Result<(Chip, Line)> {
+        let mut chip = Chip::new(&self.chip)?;
+        let line = chip.get_line(self.offset)?;
+        Ok((chip, line))
+    }
+}
+
+impl FromStr for LineSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (chip, offset) = s.rsplit_once(':').ok_or_else(|| line_spec_err(s))?;
+        let offset = offset.parse().map_err(|_| line_spec_err(s))?;
+        if chip.is_empty() {
+            return Err(line_spec_err(s));
+        }
+        let chip = if chip.contains('/') {
+            PathBuf::from(chip)
+        } else {
+            Path::new("/dev").join(chip)
+        };
+        Ok(Self { chip, offset })
+    }
+}
+
+impl std::fmt::Display for LineSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.chip.display(), self.offset)
+    }
+}
 
 /// Access to a specific GPIO Line
 ///
@@ -324,10 +1167,34 @@ impl Iterator for LineIterator {
 /// map to hardware depending on how the board is setup
 /// in the kernel.
 ///
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Line {
     chip: Arc<InnerChip>,
     offset: u32,
+    /// Line information kept by `cached_info()`, shared between clones
+    info_cache: Arc<Mutex<Option<CachedInfo>>>,
+}
+
+impl std::fmt::Debug for Line {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Line")
+            .field("chip", &self.chip)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// The contents of a `LineInfo` other than the line itself
+///
+/// Kept by `Line::cached_info()`; storing a `LineInfo` in the line would
+/// make the line refer to itself.
+#[derive(Debug, Clone)]
+struct CachedInfo {
+    flags: LineFlags,
+    name: Option<String>,
+    consumer: Option<String>,
+    debounce_period: Option<Duration>,
+    bias: Option<Bias>,
 }
 
 /// Information about a specific GPIO Line
@@ -341,6 +1208,8 @@ pub struct LineInfo {
     flags: LineFlags,
     name: Option<String>,
     consumer: Option<String>,
+    debounce_period: Option<Duration>,
+    bias: Option<Bias>,
 }
 
 bitflags! {
@@ -349,7 +1218,7 @@ bitflags! {
     /// Maps to kernel [`GPIOHANDLE_REQUEST_*`] flags.
     ///
     /// [`GPIOHANDLE_REQUEST_*`]: https://elixir.bootlin.com/linux/v4.9.127/source/include/uapi/linux/gpio.h#L58
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
     pub struct LineRequestFlags: u32 {
         const INPUT = (1 << 0);
         const OUTPUT = (1 << 1);
@@ -359,12 +1228,119 @@ bitflags! {
     }
 }
 
+impl LineRequestFlags {
+    /// Configure how the line is driven when used as an output
+    ///
+    /// Sets the flag matching `drive` (none for [`DriveMode::PushPull`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::ConflictingFlags`] error if the flags
+    /// already select a different drive mode, e.g. `OPEN_SOURCE` when
+    /// asking for [`DriveMode::OpenDrain`].
+    ///
+    /// [`DriveMode::PushPull`]: enum.DriveMode.html#variant.PushPull
+    /// [`DriveMode::OpenDrain`]: enum.DriveMode.html#variant.OpenDrain
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
+    pub fn with_drive(self, drive: DriveMode) -> Result<Self> {
+        let (flag, conflicts) = match drive {
+            DriveMode::PushPull => (Self::empty(), Self::OPEN_DRAIN | Self::OPEN_SOURCE),
+            DriveMode::OpenDrain => (Self::OPEN_DRAIN, Self::OPEN_SOURCE),
+            DriveMode::OpenSource => (Self::OPEN_SOURCE, Self::OPEN_DRAIN),
+        };
+        if self.intersects(conflicts) {
+            return Err(conflicting_flags_err(self | flag));
+        }
+        Ok(self | flag)
+    }
+
+    /// The drive mode selected by these flags
+    pub fn drive(&self) -> DriveMode {
+        if self.contains(Self::OPEN_DRAIN) {
+            DriveMode::OpenDrain
+        } else if self.contains(Self::OPEN_SOURCE) {
+            DriveMode::OpenSource
+        } else {
+            DriveMode::PushPull
+        }
+    }
+
+    /// Reject combinations of flags the kernel would refuse
+    fn validate(&self) -> Result<()> {
+        if self.contains(Self::OPEN_DRAIN | Self::OPEN_SOURCE) {
+            return Err(conflicting_flags_err(self.clone()));
+        }
+        Ok(())
+    }
+
+    /// Reject flags the kernel would refuse for an event request
+    fn validate_events(&self) -> Result<()> {
+        if self.contains(Self::OUTPUT) {
+            return Err(conflicting_flags_err(self.clone()));
+        }
+        self.validate()
+    }
+
+    /// Translate the flags into uAPI v2 line flags
+    fn v2_flags(&self) -> u64 {
+        let mut flags = 0;
+        for (v1, v2) in [
+            (Self::INPUT, ffi::GPIO_V2_LINE_FLAG_INPUT),
+            (Self::OUTPUT, ffi::GPIO_V2_LINE_FLAG_OUTPUT),
+            (Self::ACTIVE_LOW, ffi::GPIO_V2_LINE_FLAG_ACTIVE_LOW),
+            (Self::OPEN_DRAIN, ffi::GPIO_V2_LINE_FLAG_OPEN_DRAIN),
+            (Self::OPEN_SOURCE, ffi::GPIO_V2_LINE_FLAG_OPEN_SOURCE),
+        ] {
+            if self.contains(v1) {
+                flags |= v2;
+            }
+        }
+        flags
+    }
+}
+
+/// How an output line is driven
+///
+/// See [`LineRequestFlags::with_drive`].
+///
+/// [`LineRequestFlags::with_drive`]: struct.LineRequestFlags.html#method.with_drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DriveMode {
+    /// The line is actively driven both high and low
+    #[default]
+    PushPull,
+    /// The line is only driven low and floats otherwise
+    OpenDrain,
+    /// The line is only driven high and floats otherwise
+    OpenSource,
+}
+
+/// Bias (internal pull resistor) of an input line
+///
+/// See [`EventConfig::bias`] and [`LineInfo::bias`].
+///
+/// [`EventConfig::bias`]: struct.EventConfig.html#method.bias
+/// [`LineInfo::bias`]: struct.LineInfo.html#method.bias
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Bias {
+    /// Leave the bias as configured by the kernel or firmware
+    #[default]
+    AsIs,
+    /// Disable any internal pull resistor
+    Disabled,
+    /// Enable the internal pull-up resistor
+    PullUp,
+    /// Enable the internal pull-down resistor
+    PullDown,
+}
+
 bitflags! {
     /// Event request flags
     ///
     /// Maps to kernel [`GPIOEVENT_REQEST_*`] flags.
     ///
     /// [`GPIOEVENT_REQUEST_*`]: https://elixir.bootlin.com/linux/v4.9.127/source/include/uapi/linux/gpio.h#L109
+    #[derive(Debug, Clone)]
     pub struct EventRequestFlags: u32 {
         const RISING_EDGE = (1 << 0);
         const FALLING_EDGE = (1 << 1);
@@ -372,13 +1348,263 @@ bitflags! {
     }
 }
 
+impl EventRequestFlags {
+    /// Reject requests which would never deliver an event
+    fn validate(&self) -> Result<()> {
+        if !self.intersects(Self::BOTH_EDGES) {
+            return Err(no_edges_err());
+        }
+        Ok(())
+    }
+}
+
+/// Configuration for an event request made with [`Line::events_with`]
+///
+/// Event requests made with this configuration go through the GPIO
+/// uAPI v2 (Linux 5.10+) if the kernel supports it, which provides
+/// additional information on each [`LineEvent`] such as sequence numbers.
+/// The bias, debounce, clock and event buffer settings are only available
+/// with the v2 uAPI.
+///
+/// # Example
+///
+/// A debounced button connecting the line to ground:
+///
+/// ```no_run
+/// # fn main() -> Result<(), gpio_cdev::Error> {
+/// use gpio_cdev::{Bias, Chip, EventConfig, EventRequestFlags, LineRequestFlags};
+/// use std::time::Duration;
+///
+/// let mut chip = Chip::new("/dev/gpiochip0")?;
+/// let config = EventConfig::new(LineRequestFlags::INPUT, EventRequestFlags::FALLING_EDGE)
+///     .bias(Bias::PullUp)
+///     .debounce(Duration::from_millis(10));
+/// let mut button = chip.get_line(4)?.events_with(config, "button")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Line::events_with`]: struct.Line.html#method.events_with
+/// [`LineEvent`]: struct.LineEvent.html
+#[derive(Debug, Clone)]
+pub struct EventConfig {
+    handle_flags: LineRequestFlags,
+    event_flags: EventRequestFlags,
+    clock: EventClock,
+    bias: Bias,
+    debounce: Option<Duration>,
+    buffer_size: Option<u32>,
+}
+
+/// The clock used by the kernel to timestamp events
+///
+/// See [`EventConfig::clock`].
+///
+/// [`EventConfig::clock`]: struct.EventConfig.html#method.clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EventClock {
+    /// `CLOCK_MONOTONIC`, comparable with `std::time::Instant`
+    #[default]
+    Monotonic,
+    /// `CLOCK_REALTIME`, i.e. wall clock time since the Unix epoch
+    Realtime,
+}
+
+impl EventConfig {
+    /// Create an event configuration for the given handle and event flags
+    ///
+    /// Lines requested for events are always inputs, so `handle_flags`
+    /// need not include `INPUT`; it is added when the line is requested.
+    /// `handle_flags` including `OUTPUT` are rejected with
+    /// [`ErrorKind::ConflictingFlags`] when the line is requested.
+    ///
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
+    pub fn new(handle_flags: LineRequestFlags, event_flags: EventRequestFlags) -> Self {
+        Self {
+            handle_flags,
+            event_flags,
+            clock: EventClock::default(),
+            bias: Bias::default(),
+            debounce: None,
+            buffer_size: None,
+        }
+    }
+
+    /// Create a configuration for rising edge events on an input line
+    ///
+    /// This is a shorthand for `EventConfig::new(LineRequestFlags::INPUT,
+    /// EventRequestFlags::RISING_EDGE)`, to be passed to
+    /// [`Line::events_with`]:
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, EventConfig};
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let mut events = chip.get_line(4)?.events_with(EventConfig::rising(), "button")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Line::events_with`]: struct.Line.html#method.events_with
+    pub fn rising() -> Self {
+        Self::new(LineRequestFlags::INPUT, EventRequestFlags::RISING_EDGE)
+    }
+
+    /// Create a configuration for falling edge events on an input line
+    ///
+    /// See [`rising`].
+    ///
+    /// [`rising`]: struct.EventConfig.html#method.rising
+    pub fn falling() -> Self {
+        Self::new(LineRequestFlags::INPUT, EventRequestFlags::FALLING_EDGE)
+    }
+
+    /// Create a configuration for events on both edges of an input line
+    ///
+    /// See [`rising`].
+    ///
+    /// [`rising`]: struct.EventConfig.html#method.rising
+    pub fn both() -> Self {
+        Self::new(LineRequestFlags::INPUT, EventRequestFlags::BOTH_EDGES)
+    }
+
+    /// Select the bias of the line
+    ///
+    /// Defaults to [`Bias::AsIs`].  A pull-up or pull-down saves an
+    /// external resistor for e.g. buttons which would otherwise leave the
+    /// line floating.
+    ///
+    /// [`Bias::AsIs`]: enum.Bias.html#variant.AsIs
+    pub fn bias(mut self, bias: Bias) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// Debounce the line in the kernel with the given period
+    ///
+    /// Edges are only reported once the line has been stable for
+    /// `period`.  The period is used with microsecond resolution; not all
+    /// hardware supports debouncing, in which case the kernel emulates it
+    /// in software.
+    pub fn debounce(mut self, period: Duration) -> Self {
+        self.debounce = Some(period);
+        self
+    }
+
+    /// Select the clock used to timestamp events
+    ///
+    /// Defaults to [`EventClock::Monotonic`], which is what
+    /// `std::time::Instant` uses and is best suited for measuring the time
+    /// between events.  [`EventClock::Realtime`] timestamps are wall clock
+    /// times which are easier to correlate with logs.
+    ///
+    /// [`EventClock::Monotonic`]: enum.EventClock.html#variant.Monotonic
+    /// [`EventClock::Realtime`]: enum.EventClock.html#variant.Realtime
+    pub fn clock(mut self, clock: EventClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Ask the kernel to queue up to `size` events before dropping any
+    ///
+    /// By default the kernel queues 16 events per requested line.  A
+    /// larger buffer makes it less likely to lose events on a fast signal
+    /// when they are not read in time.  The size is a hint: the kernel
+    /// caps it (at 1024 events in current kernels).  It can only be chosen
+    /// when the line is requested, not changed with
+    /// [`LineEventHandle::set_config`].
+    ///
+    /// [`LineEventHandle::set_config`]: struct.LineEventHandle.html#method.set_config
+    pub fn event_buffer_size(mut self, size: u32) -> Self {
+        self.buffer_size = Some(size);
+        self
+    }
+
+    /// The flags the line will be configured with
+    pub fn handle_flags(&self) -> LineRequestFlags {
+        self.handle_flags.clone()
+    }
+
+    /// The edges that will generate events
+    pub fn event_flags(&self) -> EventRequestFlags {
+        self.event_flags.clone()
+    }
+
+    /// The clock that will be used to timestamp events
+    pub fn event_clock(&self) -> EventClock {
+        self.clock
+    }
+
+    /// The bias the line will be configured with
+    pub fn line_bias(&self) -> Bias {
+        self.bias
+    }
+
+    /// The debounce period the line will be configured with, if any
+    pub fn debounce_period(&self) -> Option<Duration> {
+        self.debounce
+    }
+
+    /// The size of the kernel event buffer asked for, if any
+    pub fn buffer_size(&self) -> Option<u32> {
+        self.buffer_size
+    }
+
+    /// Whether the configuration uses settings only the v2 uAPI supports
+    fn requires_v2(&self) -> bool {
+        self.clock != EventClock::Monotonic
+            || self.bias != Bias::AsIs
+            || self.debounce.is_some()
+            || self.buffer_size.is_some()
+    }
+
+    /// Translate the configuration into a uAPI v2 configuration for `n` lines
+    fn v2_config(&self, n: usize) -> ffi::gpio_v2_line_config {
+        let mut config: ffi::gpio_v2_line_config = unsafe { mem::zeroed() };
+        config.flags = self.v2_flags();
+        if let Some(period) = self.debounce {
+            let attr = &mut config.attrs[0];
+            attr.attr.id = ffi::GPIO_V2_LINE_ATTR_ID_DEBOUNCE;
+            attr.attr.value.debounce_period_us =
+                u32::try_from(period.as_micros()).unwrap_or(u32::MAX);
+            attr.mask = line_mask(n);
+            config.num_attrs = 1;
+        }
+        config
+    }
+
+    /// Translate the configuration into uAPI v2 line flags
+    fn v2_flags(&self) -> u64 {
+        // The v2 uAPI requires a direction for edge detection and bias,
+        // while the v1 uAPI implies an input for event requests
+        let mut flags = self.handle_flags.v2_flags() | ffi::GPIO_V2_LINE_FLAG_INPUT;
+        if self.event_flags.contains(EventRequestFlags::RISING_EDGE) {
+            flags |= ffi::GPIO_V2_LINE_FLAG_EDGE_RISING;
+        }
+        if self.event_flags.contains(EventRequestFlags::FALLING_EDGE) {
+            flags |= ffi::GPIO_V2_LINE_FLAG_EDGE_FALLING;
+        }
+        if self.clock == EventClock::Realtime {
+            flags |= ffi::GPIO_V2_LINE_FLAG_EVENT_CLOCK_REALTIME;
+        }
+        flags |= match self.bias {
+            Bias::AsIs => 0,
+            Bias::Disabled => ffi::GPIO_V2_LINE_FLAG_BIAS_DISABLED,
+            Bias::PullUp => ffi::GPIO_V2_LINE_FLAG_BIAS_PULL_UP,
+            Bias::PullDown => ffi::GPIO_V2_LINE_FLAG_BIAS_PULL_DOWN,
+        };
+        flags
+    }
+}
+
 bitflags! {
     /// Informational Flags
     ///
     /// Maps to kernel [`GPIOLINE_FLAG_*`] flags.
     ///
     /// [`GPIOLINE_FLAG_*`]: https://elixir.bootlin.com/linux/v4.9.127/source/include/uapi/linux/gpio.h#L29
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct LineFlags: u32 {
         const KERNEL = (1 << 0);
         const IS_OUT = (1 << 1);
@@ -389,7 +1615,7 @@ bitflags! {
 }
 
 /// In or Out
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum LineDirection {
     In,
     Out,
@@ -408,11 +1634,26 @@ impl Line {
         if offset >= chip.lines {
             return Err(offset_err(offset));
         }
-        Ok(Self { chip, offset })
+        Ok(Self {
+            chip,
+            offset,
+            info_cache: Arc::new(Mutex::new(None)),
+        })
     }
 
     /// Get info about the line from the kernel.
+    ///
+    /// The v2 uAPI is used if the kernel supports it, as it reports more
+    /// details (such as the debounce period); otherwise this falls back
+    /// to the v1 uAPI.
     pub fn info(&self) -> Result<LineInfo> {
+        if self.chip.supports_v2() {
+            let mut line_info: ffi::gpio_v2_line_info = unsafe { mem::zeroed() };
+            line_info.offset = self.offset;
+            ffi::gpio_v2_get_lineinfo_ioctl(self.chip.file.as_raw_fd(), &mut line_info)?;
+            return Ok(LineInfo::from_v2(self.clone(), &line_info));
+        }
+
         let mut line_info = ffi::gpioline_info {
             line_offset: self.offset,
             flags: 0,
@@ -426,9 +1667,70 @@ impl Line {
             flags: LineFlags::from_bits_truncate(line_info.flags),
             name: unsafe { cstrbuf_to_string(&line_info.name[..]) },
             consumer: unsafe { cstrbuf_to_string(&line_info.consumer[..]) },
+            debounce_period: None,
+            bias: None,
         })
     }
 
+    /// Get info about the line, reusing the result of an earlier call
+    ///
+    /// The first call queries the kernel like [`info`]; later calls return
+    /// the same information without an ioctl until [`refresh_info`] is
+    /// called.  The cache is shared with clones of this `Line`, but not
+    /// with other `Line`s for the same offset.
+    ///
+    /// Line information only changes when the line is requested, released
+    /// or reconfigured, but the cache is not updated automatically when
+    /// that happens (even when done through this `Line`); call
+    /// [`refresh_info`] afterwards.  [`info`] itself never uses the cache.
+    ///
+    /// [`info`]: struct.Line.html#method.info
+    /// [`refresh_info`]: struct.Line.html#method.refresh_info
+    pub fn cached_info(&self) -> Result<LineInfo> {
+        let mut cache = self.info_cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = &*cache {
+            return Ok(LineInfo {
+                line: self.clone(),
+                flags: cached.flags.clone(),
+                name: cached.name.clone(),
+                consumer: cached.consumer.clone(),
+                debounce_period: cached.debounce_period,
+                bias: cached.bias,
+            });
+        }
+        let info = self.info()?;
+        *cache = Some(CachedInfo {
+            flags: info.flags.clone(),
+            name: info.name.clone(),
+            consumer: info.consumer.clone(),
+            debounce_period: info.debounce_period,
+            bias: info.bias,
+        });
+        Ok(info)
+    }
+
+    /// Discard the information kept by [`cached_info`]
+    ///
+    /// The next call to [`cached_info`] queries the kernel again.
+    ///
+    /// [`cached_info`]: struct.Line.html#method.cached_info
+    pub fn refresh_info(&self) {
+        *self.info_cache.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Check whether the line can currently be requested
+    ///
+    /// This is a shorthand for querying [`info()`] and checking that the
+    /// line is neither reserved by the kernel nor in use by another
+    /// consumer.  The answer may of course be stale by the time the line
+    /// is actually requested.
+    ///
+    /// [`info()`]: struct.Line.html#method.info
+    pub fn is_available(&self) -> Result<bool> {
+        let info = self.info()?;
+        Ok(!info.is_kernel() && !info.is_used())
+    }
+
     /// Offset of this line within its parent chip
     pub fn offset(&self) -> u32 {
         self.offset
@@ -451,7 +1753,9 @@ impl Line {
     /// For an output, the `default` parameter specifies the value
     /// the line should have when it is configured as an output.  The
     /// `consumer` string should describe the process consuming the
-    /// line (this will be truncated to 31 characters if too long).
+    /// line (this will be truncated to 31 bytes if too long, without
+    /// splitting a UTF-8 character).  An empty `consumer` leaves the
+    /// line without a label.
     ///
     /// # Errors
     ///
@@ -461,17 +1765,95 @@ impl Line {
     ///
     /// One possible cause for an error here would be if the line is
     /// already in use.  One can check for this prior to making the
-    /// request using [`is_kernel`].
+    /// request using [`is_available`].  A line in use results in an
+    /// [`ErrorKind::Busy`] error naming the current consumer, if it has
+    /// one; its [`Error::raw_os_error`] is still `EBUSY`.
+    ///
+    /// Flags selecting both `OPEN_DRAIN` and `OPEN_SOURCE` are rejected
+    /// with [`ErrorKind::ConflictingFlags`] before the kernel is asked, as
+    /// are default values other than 0 or 1 with
+    /// [`ErrorKind::InvalidValue`] and consumers containing a NUL
+    /// character with [`ErrorKind::InvalidConsumer`].
     ///
+    /// The line is requested through the v2 uAPI if the kernel supports
+    /// it (see [`Chip::supports_v2`]), which enables
+    /// [`LineHandle::set_active_low`].
+    ///
+    /// [`ErrorKind::InvalidConsumer`]: errors/enum.ErrorKind.html#variant.InvalidConsumer
     /// [`Error`]: errors/struct.Error.html
     /// [`ErrorKind::Ioctl`]: errors/enum.ErrorKind.html#variant.Ioctl
-    /// [`is_kernel`]: struct.Line.html#method.is_kernel
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
+    /// [`ErrorKind::InvalidValue`]: errors/enum.ErrorKind.html#variant.InvalidValue
+    /// [`is_available`]: struct.Line.html#method.is_available
+    /// [`ErrorKind::Busy`]: errors/enum.ErrorKind.html#variant.Busy
+    /// [`Error::raw_os_error`]: errors/struct.Error.html#method.raw_os_error
+    /// [`Chip::supports_v2`]: struct.Chip.html#method.supports_v2
+    /// [`LineHandle::set_active_low`]: struct.LineHandle.html#method.set_active_low
     pub fn request(
         &self,
         flags: LineRequestFlags,
         default: u8,
         consumer: &str,
     ) -> Result<LineHandle> {
+        self.request_bytes(flags, default, consumer_label(consumer)?)
+    }
+
+    /// Request this line with the consumer label of its chip
+    ///
+    /// This is [`request`] with the label set with [`Chip::set_consumer`].
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineRequestFlags};
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// chip.set_consumer("my-daemon");
+    /// let led = chip.get_line(4)?.request_default_consumer(LineRequestFlags::OUTPUT, 0)?;
+    /// let button = chip.get_line(5)?.request_default_consumer(LineRequestFlags::INPUT, 0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`request`]: struct.Line.html#method.request
+    /// [`Chip::set_consumer`]: struct.Chip.html#method.set_consumer
+    pub fn request_default_consumer(
+        &self,
+        flags: LineRequestFlags,
+        default: u8,
+    ) -> Result<LineHandle> {
+        let consumer = self.chip.consumer().clone();
+        self.request(flags, default, &consumer)
+    }
+
+    /// Request this line with a consumer label given as raw bytes
+    ///
+    /// This is like [`request`], but the consumer label does not need to
+    /// be UTF-8: up to 31 bytes of `consumer` are stored as they are, as
+    /// the kernel does not interpret the label.  Labels containing a NUL
+    /// byte cannot be stored and are rejected with an
+    /// [`ErrorKind::InvalidConsumer`] error.
+    ///
+    /// [`request`]: struct.Line.html#method.request
+    /// [`ErrorKind::InvalidConsumer`]: errors/enum.ErrorKind.html#variant.InvalidConsumer
+    pub fn request_bytes(
+        &self,
+        flags: LineRequestFlags,
+        default: u8,
+        consumer: &[u8],
+    ) -> Result<LineHandle> {
+        flags.validate()?;
+        validate_value(default)?;
+        validate_consumer(consumer)?;
+        if self.chip.supports_v2() {
+            let config = v2_line_config(&flags, &[default]);
+            let file = v2_line_request(slice::from_ref(self), config, 0, consumer)?;
+            return Ok(LineHandle {
+                line: self.clone(),
+                flags,
+                file,
+                abi: Abi::V2,
+            });
+        }
         // prepare the request; the kernel consumes some of these values and will
         // set the fd for us.
         let mut request = ffi::gpiohandle_request {
@@ -485,20 +1867,111 @@ impl Line {
         request.lineoffsets[0] = self.offset;
         request.default_values[0] = default;
         unsafe {
-            rstr_lcpy(
+            bytes_lcpy(
                 request.consumer_label[..].as_mut_ptr(),
                 consumer,
                 request.consumer_label.len(),
             );
         }
-        ffi::gpio_get_linehandle_ioctl(self.chip.file.as_raw_fd(), &mut request)?;
+        ffi::gpio_get_linehandle_ioctl(self.chip.file.as_raw_fd(), &mut request)
+            .map_err(|e| map_busy(slice::from_ref(self), e))?;
         Ok(LineHandle {
             line: self.clone(),
             flags,
             file: unsafe { File::from_raw_fd(request.fd) },
+            abi: Abi::V1,
         })
     }
 
+    /// Request this line, retrying while it is busy
+    ///
+    /// This is like [`request`], but if the kernel reports the line as busy
+    /// (`EBUSY`) the request is retried, up to `attempts` times in total.
+    /// The first retry waits `delay` and every further retry waits twice as
+    /// long as the previous one.  This helps when re-requesting a line
+    /// right after releasing it, or when it is briefly held by another
+    /// process.  An `attempts` of `0` is treated as `1`.
+    ///
+    /// Errors other than `EBUSY` are returned immediately; once all
+    /// attempts are used up the last `EBUSY` error is returned.
+    ///
+    /// [`request`]: struct.Line.html#method.request
+    pub fn request_retry(
+        &self,
+        flags: LineRequestFlags,
+        default: u8,
+        consumer: &str,
+        attempts: u32,
+        delay: Duration,
+    ) -> Result<LineHandle> {
+        let mut delay = delay;
+        let mut attempt = 1;
+        loop {
+            match self.request(flags.clone(), default, consumer) {
+                Err(e) if e.raw_os_error() == Some(libc::EBUSY) && attempt < attempts => {
+                    std::thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Request this line as an input which cannot be driven
+    ///
+    /// This is like [`request`] with `INPUT` added to `flags`, but the
+    /// returned [`InputLineHandle`] has no way to set the value of the
+    /// line, so a monitoring-only program cannot drive it by accident.
+    ///
+    /// # Errors
+    ///
+    /// Passing `OUTPUT` in `flags` results in an
+    /// [`ErrorKind::ConflictingFlags`] error.  Otherwise errors are as for
+    /// [`request`].
+    ///
+    /// [`request`]: struct.Line.html#method.request
+    /// [`InputLineHandle`]: struct.InputLineHandle.html
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
+    pub fn request_input(
+        &self,
+        flags: LineRequestFlags,
+        consumer: &str,
+    ) -> Result<InputLineHandle> {
+        if flags.contains(LineRequestFlags::OUTPUT) {
+            return Err(conflicting_flags_err(flags | LineRequestFlags::INPUT));
+        }
+        let handle = self.request(flags | LineRequestFlags::INPUT, 0, consumer)?;
+        Ok(InputLineHandle(handle))
+    }
+
+    /// Request this line as an output
+    ///
+    /// This is like [`request`] with `OUTPUT` added to `flags`, returning an
+    /// [`OutputLineHandle`].
+    ///
+    /// # Errors
+    ///
+    /// Passing `INPUT` in `flags` results in an
+    /// [`ErrorKind::ConflictingFlags`] error.  Otherwise errors are as for
+    /// [`request`].
+    ///
+    /// [`request`]: struct.Line.html#method.request
+    /// [`OutputLineHandle`]: struct.OutputLineHandle.html
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
+    pub fn request_output(
+        &self,
+        flags: LineRequestFlags,
+        default: u8,
+        consumer: &str,
+    ) -> Result<OutputLineHandle> {
+        if flags.contains(LineRequestFlags::INPUT) {
+            return Err(conflicting_flags_err(flags | LineRequestFlags::OUTPUT));
+        }
+        let handle = self.request(flags | LineRequestFlags::OUTPUT, default, consumer)?;
+        Ok(OutputLineHandle(handle))
+    }
+
     /// Get an event handle that can be used as a blocking iterator over
     /// the events (state changes) for this Line
     ///
@@ -536,12 +2009,27 @@ impl Line {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// # Errors
+    ///
+    /// `event_flags` without any edge would never produce an event and
+    /// result in an [`ErrorKind::NoEdges`] error.  Lines requested for
+    /// events are inputs, so `handle_flags` including `OUTPUT` result in an
+    /// [`ErrorKind::ConflictingFlags`] error.  Consumer labels containing a
+    /// NUL character result in an [`ErrorKind::InvalidConsumer`] error.
+    ///
+    /// [`ErrorKind::InvalidConsumer`]: errors/enum.ErrorKind.html#variant.InvalidConsumer
+    /// [`ErrorKind::NoEdges`]: errors/enum.ErrorKind.html#variant.NoEdges
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
     pub fn events(
         &self,
         handle_flags: LineRequestFlags,
         event_flags: EventRequestFlags,
         consumer: &str,
     ) -> Result<LineEventHandle> {
+        handle_flags.validate_events()?;
+        event_flags.validate()?;
+        let consumer = consumer_label(consumer)?;
         let mut request = ffi::gpioevent_request {
             lineoffset: self.offset,
             handleflags: handle_flags.bits(),
@@ -551,18 +2039,111 @@ impl Line {
         };
 
         unsafe {
-            rstr_lcpy(
+            bytes_lcpy(
                 request.consumer_label[..].as_mut_ptr(),
                 consumer,
                 request.consumer_label.len(),
             );
         }
-        ffi::gpio_get_lineevent_ioctl(self.chip.file.as_raw_fd(), &mut request)?;
+        ffi::gpio_get_lineevent_ioctl(self.chip.file.as_raw_fd(), &mut request)
+            .map_err(|e| map_busy(slice::from_ref(self), e))?;
 
-        Ok(LineEventHandle {
-            line: self.clone(),
-            file: unsafe { File::from_raw_fd(request.fd) },
-        })
+        Ok(LineEventHandle::new(
+            self.clone(),
+            unsafe { File::from_raw_fd(request.fd) },
+            Abi::V1,
+        ))
+    }
+
+    /// Get an event handle for this line configured by an [`EventConfig`]
+    ///
+    /// This behaves like [`events`] but additionally allows configuring
+    /// the bias, debounce period and event clock of the line.  If the
+    /// kernel supports it (Linux 5.10+, see [`Chip::supports_v2`]) the
+    /// request is made through the v2 character device ABI.  Events read
+    /// from the returned handle then carry sequence numbers which can be
+    /// used to detect events lost due to an overflow of the kernel event
+    /// queue; see [`LineEvent::seqno`].  Otherwise the request falls back
+    /// to the v1 ABI.
+    ///
+    /// # Errors
+    ///
+    /// On kernels without support for the v2 uAPI, a configuration with a
+    /// bias, a debounce period or a clock other than the default results
+    /// in an [`ErrorKind::RequiresV2`] error.  As with [`events`], the line
+    /// is always requested as an input: `INPUT` is implied, and handle
+    /// flags including `OUTPUT` result in an
+    /// [`ErrorKind::ConflictingFlags`] error.
+    ///
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
+    /// [`EventConfig`]: struct.EventConfig.html
+    /// [`events`]: struct.Line.html#method.events
+    /// [`Chip::supports_v2`]: struct.Chip.html#method.supports_v2
+    /// [`LineEvent::seqno`]: struct.LineEvent.html#method.seqno
+    /// [`ErrorKind::RequiresV2`]: errors/enum.ErrorKind.html#variant.RequiresV2
+    pub fn events_with(&self, config: EventConfig, consumer: &str) -> Result<LineEventHandle> {
+        config.handle_flags.validate_events()?;
+        config.event_flags.validate()?;
+        if !self.chip.supports_v2() {
+            if config.requires_v2() {
+                return Err(requires_v2_err());
+            }
+            return self.events(config.handle_flags, config.event_flags, consumer);
+        }
+        let consumer = consumer_label(consumer)?;
+        let buffer_size = config.buffer_size.unwrap_or(0);
+        let file = v2_line_request(
+            slice::from_ref(self),
+            config.v2_config(1),
+            buffer_size,
+            consumer,
+        )?;
+        Ok(LineEventHandle::new(self.clone(), file, Abi::V2))
+    }
+
+    /// Block until the given edge occurs on this line
+    ///
+    /// The line is requested as an input for events matching `edge` only,
+    /// and released again before returning.  Returns `Ok(None)` if no such
+    /// edge occurred within `timeout`; with a `timeout` of `None` this waits
+    /// forever.
+    ///
+    /// Edges that occur before the line has been requested are not seen,
+    /// so this is not suitable for waiting on a level (a line which is
+    /// already high will not produce a rising edge).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, EventType};
+    /// use std::time::Duration;
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let button = chip.get_line(4)?;
+    /// match button.wait_for_edge(EventType::RisingEdge, "wait-button", Some(Duration::from_secs(5)))? {
+    ///     Some(event) => println!("Pressed at {}", event.timestamp()),
+    ///     None => println!("Timed out"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wait_for_edge(
+        &self,
+        edge: EventType,
+        consumer: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Option<LineEvent>> {
+        let event_flags = match edge {
+            EventType::RisingEdge => EventRequestFlags::RISING_EDGE,
+            EventType::FallingEdge => EventRequestFlags::FALLING_EDGE,
+        };
+        let mut handle = self.events(LineRequestFlags::INPUT, event_flags, consumer)?;
+        if poll_readable(handle.as_raw_fd(), timeout_ms(timeout))? {
+            handle.get_event().map(Some)
+        } else {
+            Ok(None)
+        }
     }
 
     #[cfg(feature = "async-tokio")]
@@ -579,19 +2160,84 @@ impl Line {
 }
 
 impl LineInfo {
+    /// Build the info from the v2 line info, mapping flags to their v1 equivalent
+    fn from_v2(line: Line, info: &ffi::gpio_v2_line_info) -> Self {
+        let mut flags = LineFlags::empty();
+        for (v2, v1) in [
+            (ffi::GPIO_V2_LINE_FLAG_USED, LineFlags::KERNEL),
+            (ffi::GPIO_V2_LINE_FLAG_OUTPUT, LineFlags::IS_OUT),
+            (ffi::GPIO_V2_LINE_FLAG_ACTIVE_LOW, LineFlags::ACTIVE_LOW),
+            (ffi::GPIO_V2_LINE_FLAG_OPEN_DRAIN, LineFlags::OPEN_DRAIN),
+            (ffi::GPIO_V2_LINE_FLAG_OPEN_SOURCE, LineFlags::OPEN_SOURCE),
+        ] {
+            if info.flags & v2 != 0 {
+                flags |= v1;
+            }
+        }
+        let num_attrs = min(info.num_attrs as usize, info.attrs.len());
+        let debounce_period = info.attrs[..num_attrs]
+            .iter()
+            .find(|attr| attr.id == ffi::GPIO_V2_LINE_ATTR_ID_DEBOUNCE)
+            .map(|attr| Duration::from_micros(unsafe { attr.value.debounce_period_us }.into()));
+        let bias = if info.flags & ffi::GPIO_V2_LINE_FLAG_BIAS_PULL_UP != 0 {
+            Bias::PullUp
+        } else if info.flags & ffi::GPIO_V2_LINE_FLAG_BIAS_PULL_DOWN != 0 {
+            Bias::PullDown
+        } else if info.flags & ffi::GPIO_V2_LINE_FLAG_BIAS_DISABLED != 0 {
+            Bias::Disabled
+        } else {
+            Bias::AsIs
+        };
+
+        LineInfo {
+            line,
+            flags,
+            name: unsafe { cstrbuf_to_string(&info.name[..]) },
+            consumer: unsafe { cstrbuf_to_string(&info.consumer[..]) },
+            debounce_period,
+            bias: Some(bias),
+        }
+    }
+
     /// Get a handle to the line that this info represents
     pub fn line(&self) -> &Line {
         &self.line
     }
 
-    /// Name assigned to this chip if assigned
+    /// The name of this GPIO line, such as the output pin of the line on the
+    /// chip, a rail or a pin header name on a board, as specified by the gpio
+    /// chip.
+    ///
+    /// The name is fixed by the driver or device tree and does not change
+    /// when the line is requested.
     pub fn name(&self) -> Option<&str> {
         self.name.as_deref()
     }
 
-    /// The name of this GPIO line, such as the output pin of the line on the
-    /// chip, a rail or a pin header name on a board, as specified by the gpio
-    /// chip.
+    /// The label of whoever currently holds the line
+    ///
+    /// For lines requested from userspace this is the `consumer` passed
+    /// with the request.  For lines held by the kernel it is typically the
+    /// name of the driver or subsystem using the line.  `None` if the line
+    /// is not in use or the consumer did not provide a label.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::Chip;
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let info = chip.get_line(4)?.info()?;
+    /// println!(
+    ///     "line {} is named {:?} and used by {:?}",
+    ///     info.line().offset(),
+    ///     info.name(),
+    ///     info.consumer()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn consumer(&self) -> Option<&str> {
         self.consumer.as_deref()
     }
@@ -608,9 +2254,20 @@ impl LineInfo {
         }
     }
 
-    /// True if the any flags for the device are set (input or output)
+    /// True if the line is currently requested, either by the kernel or from
+    /// userspace
+    ///
+    /// This matches the uAPI v2 `GPIO_V2_LINE_FLAG_USED` flag; other flags
+    /// such as active low may be set on lines which are not in use.
+    ///
+    /// The kernel reports a single flag for lines in use (`KERNEL` in the
+    /// v1 uAPI, `USED` in the v2 uAPI), so this is the same as
+    /// [`is_kernel`].  It does not tell lines requested by a driver apart
+    /// from lines requested from userspace.
+    ///
+    /// [`is_kernel`]: struct.LineInfo.html#method.is_kernel
     pub fn is_used(&self) -> bool {
-        !self.flags.is_empty()
+        self.flags.contains(LineFlags::KERNEL)
     }
 
     /// True if this line is being used by something else in the kernel
@@ -618,11 +2275,33 @@ impl LineInfo {
     /// If another driver or subsystem in the kernel is using the line
     /// then it cannot be used via the cdev interface. See [relevant kernel code].
     ///
+    /// Note that the kernel also reports lines requested by other processes
+    /// through the character device as in use by the kernel.
+    ///
     /// [relevant kernel code]: https://elixir.bootlin.com/linux/v4.9.127/source/drivers/gpio/gpiolib.c#L938
     pub fn is_kernel(&self) -> bool {
         self.flags.contains(LineFlags::KERNEL)
     }
 
+    /// The debounce period configured for this line, if any
+    ///
+    /// Only reported by kernels supporting the v2 uAPI; always `None` when
+    /// the info was obtained through the v1 uAPI.
+    pub fn debounce_period(&self) -> Option<Duration> {
+        self.debounce_period
+    }
+
+    /// The bias configured for this line
+    ///
+    /// The bias is only reported by kernels supporting the v2 uAPI; with
+    /// the v1 uAPI this returns `None`.  [`Bias::AsIs`] means that the
+    /// kernel reports no bias configured through the GPIO character device.
+    ///
+    /// [`Bias::AsIs`]: enum.Bias.html#variant.AsIs
+    pub fn bias(&self) -> Option<Bias> {
+        self.bias
+    }
+
     /// True if this line is marked as active low in the kernel
     pub fn is_active_low(&self) -> bool {
         self.flags.contains(LineFlags::ACTIVE_LOW)
@@ -637,6 +2316,29 @@ impl LineInfo {
     pub fn is_open_source(&self) -> bool {
         self.flags.contains(LineFlags::OPEN_SOURCE)
     }
+
+    /// The request flags matching the current configuration of the line
+    ///
+    /// Lines which are outputs map to `OUTPUT`, all others to `INPUT`;
+    /// `ACTIVE_LOW`, `OPEN_DRAIN` and `OPEN_SOURCE` are carried over.  This
+    /// allows requesting a line again with the configuration it has now.
+    pub fn as_request_flags(&self) -> LineRequestFlags {
+        let mut flags = if self.flags.contains(LineFlags::IS_OUT) {
+            LineRequestFlags::OUTPUT
+        } else {
+            LineRequestFlags::INPUT
+        };
+        for (info, request) in [
+            (LineFlags::ACTIVE_LOW, LineRequestFlags::ACTIVE_LOW),
+            (LineFlags::OPEN_DRAIN, LineRequestFlags::OPEN_DRAIN),
+            (LineFlags::OPEN_SOURCE, LineRequestFlags::OPEN_SOURCE),
+        ] {
+            if self.flags.contains(info) {
+                flags |= request;
+            }
+        }
+        flags
+    }
 }
 
 /// Handle for interacting with a "requested" line
@@ -648,14 +2350,86 @@ impl LineInfo {
 /// is the go-between for callers and that file descriptor.
 ///
 /// [`Line::request`]: struct.Line.html#method.request
-#[derive(Debug)]
 pub struct LineHandle {
     line: Line,
     flags: LineRequestFlags,
     file: File,
+    abi: Abi,
+}
+
+impl std::fmt::Debug for LineHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("LineHandle")
+            .field("chip", &self.line.chip.name)
+            .field("offset", &self.line.offset)
+            .field("flags", &self.flags)
+            .finish()
+    }
 }
 
 impl LineHandle {
+    /// Rebuild a handle from the file descriptor of a request of `line`
+    ///
+    /// This is the counterpart of [`into_raw_fd`], e.g. for a privileged
+    /// helper which requests the lines and passes their file descriptors
+    /// to an unprivileged worker over a unix socket (`SCM_RIGHTS`).  The
+    /// worker obtains `line` from its own [`Chip`], which only needs to be
+    /// opened for reading.  The standard `FromRawFd` trait cannot be
+    /// implemented as the handle needs to know its line.
+    ///
+    /// `flags` should be those the line was requested with; they are
+    /// reported by [`flags`] but not checked against the kernel.  Whether
+    /// the request was made through the v1 or the v2 uAPI is detected by
+    /// reading the value of the line.
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineHandle, LineRequestFlags};
+    /// use std::os::unix::io::IntoRawFd;
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let line = chip.get_line(4)?;
+    /// let fd = line.request(LineRequestFlags::OUTPUT, 0, "helper")?.into_raw_fd();
+    /// // ... pass `fd` on, e.g. to a child process ...
+    /// let handle = unsafe { LineHandle::from_raw_fd(line, LineRequestFlags::OUTPUT, fd)? };
+    /// handle.set_value(1)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the value of the line cannot be read through either uAPI (e.g.
+    /// because `fd` is not a line request), the error of the v1 ioctl is
+    /// returned and `fd` is closed.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open file descriptor owned by the caller, which
+    /// passes to the returned handle and is closed when it is dropped.  It
+    /// must refer to a request of `line` alone.
+    ///
+    /// [`into_raw_fd`]: struct.LineHandle.html#method.into_raw_fd
+    /// [`Chip`]: struct.Chip.html
+    /// [`flags`]: struct.LineHandle.html#method.flags
+    pub unsafe fn from_raw_fd(line: Line, flags: LineRequestFlags, fd: RawFd) -> Result<Self> {
+        let file = File::from_raw_fd(fd);
+        let mut v2_data = ffi::gpio_v2_line_values { bits: 0, mask: 1 };
+        let abi = if ffi::gpio_v2_line_get_values_ioctl(fd, &mut v2_data).is_ok() {
+            Abi::V2
+        } else {
+            let mut v1_data: ffi::gpiohandle_data = mem::zeroed();
+            ffi::gpiohandle_get_line_values_ioctl(fd, &mut v1_data)?;
+            Abi::V1
+        };
+        Ok(LineHandle {
+            line,
+            flags,
+            file,
+            abi,
+        })
+    }
+
     /// Request the current state of this Line from the kernel
     ///
     /// This call is expected to succeed for both input and output
@@ -666,11 +2440,42 @@ impl LineHandle {
     /// This value should be 0 or 1 which a "1" representing that
     /// the line is active.  Usually this means that the line is
     /// at logic-level high but it could mean the opposite if the
-    /// line has been marked as being `ACTIVE_LOW`.
+    /// line has been marked as being `ACTIVE_LOW`.  This is the
+    /// logical value; use [`get_physical_value`] for the electrical
+    /// level.
+    ///
+    /// [`get_physical_value`]: struct.LineHandle.html#method.get_physical_value
     pub fn get_value(&self) -> Result<u8> {
-        let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
-        ffi::gpiohandle_get_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
-        Ok(data.values[0])
+        match self.abi {
+            Abi::V1 => {
+                let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
+                ffi::gpiohandle_get_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+                Ok(data.values[0])
+            }
+            Abi::V2 => {
+                let mut data = ffi::gpio_v2_line_values { bits: 0, mask: 1 };
+                ffi::gpio_v2_line_get_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+                Ok((data.bits & 1) as u8)
+            }
+        }
+    }
+
+    /// Request the electrical level of this Line from the kernel
+    ///
+    /// Unlike [`get_value`], which returns whether the line is active,
+    /// this undoes the inversion applied for lines requested as
+    /// `ACTIVE_LOW`: "1" always means the line is at logic-level high
+    /// and "0" that it is low.  For lines requested without `ACTIVE_LOW`
+    /// both methods return the same value.
+    ///
+    /// [`get_value`]: struct.LineHandle.html#method.get_value
+    pub fn get_physical_value(&self) -> Result<u8> {
+        let value = self.get_value()?;
+        if self.flags.contains(LineRequestFlags::ACTIVE_LOW) {
+            Ok(value ^ 1)
+        } else {
+            Ok(value)
+        }
     }
 
     /// Request that the line be driven to the specified value
@@ -680,66 +2485,555 @@ impl LineHandle {
     /// logic level high unless the line has been marked as `ACTIVE_LOW`.
     ///
     /// Calling `set_value` on a line that is not an output will
-    /// likely result in an error (from the kernel).
+    /// likely result in an error (from the kernel).  Values other than
+    /// 0 or 1 are rejected with [`ErrorKind::InvalidValue`].
+    ///
+    /// [`ErrorKind::InvalidValue`]: errors/enum.ErrorKind.html#variant.InvalidValue
     pub fn set_value(&self, value: u8) -> Result<()> {
-        let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
-        data.values[0] = value;
-        ffi::gpiohandle_set_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+        validate_value(value)?;
+        match self.abi {
+            Abi::V1 => {
+                let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
+                data.values[0] = value;
+                ffi::gpiohandle_set_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+            }
+            Abi::V2 => {
+                let mut data = ffi::gpio_v2_line_values {
+                    bits: u64::from(value != 0),
+                    mask: 1,
+                };
+                ffi::gpio_v2_line_set_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drive the line to a new value and return its previous value
+    ///
+    /// The current value is read immediately before the new one is set.
+    /// These are two separate calls, so a change made by another process in
+    /// between is not noticed, which is fine for a single user of the line.
+    pub fn swap_value(&self, value: u8) -> Result<u8> {
+        validate_value(value)?;
+        let old = self.get_value()?;
+        self.set_value(value)?;
+        Ok(old)
+    }
+
+    /// Block until the line has the given value
+    ///
+    /// Returns `Ok(true)` as soon as the line reads as `value`, immediately
+    /// if it already does, and `Ok(false)` if that did not happen within
+    /// `timeout`; with a `timeout` of `None` this waits forever.  Unlike
+    /// [`Line::wait_for_edge`] this does not miss a level which is reached
+    /// before waiting starts.
+    ///
+    /// While waiting, edge detection is temporarily enabled on the line by
+    /// reconfiguring the request, and disabled again before returning.
+    ///
+    /// # Errors
+    ///
+    /// The kernel only supports edge detection on inputs, so lines not
+    /// requested with [`LineRequestFlags::INPUT`] result in an
+    /// [`ErrorKind::NotAnInput`] error.
+    ///
+    /// Only lines requested through the v2 uAPI can be reconfigured while
+    /// requested; for other handles an [`ErrorKind::RequiresV2`] error is
+    /// returned.  There is no fallback for the v1 uAPI: events can only be
+    /// requested there in a separate request of the line, which the kernel
+    /// refuses while this handle holds it.  Use [`Line::events`] instead of
+    /// a `LineHandle` to wait for inputs on such kernels.
+    ///
+    /// [`Line::wait_for_edge`]: struct.Line.html#method.wait_for_edge
+    /// [`LineRequestFlags::INPUT`]: struct.LineRequestFlags.html#associatedconstant.INPUT
+    /// [`ErrorKind::NotAnInput`]: errors/enum.ErrorKind.html#variant.NotAnInput
+    /// [`ErrorKind::RequiresV2`]: errors/enum.ErrorKind.html#variant.RequiresV2
+    /// [`Line::events`]: struct.Line.html#method.events
+    pub fn wait_until(&self, value: u8, timeout: Option<Duration>) -> Result<bool> {
+        validate_value(value)?;
+        if !self.flags.contains(LineRequestFlags::INPUT) {
+            return Err(not_an_input_err(self.line.offset()));
+        }
+        if self.abi != Abi::V2 {
+            return Err(requires_v2_err());
+        }
+        if self.get_value()? == value {
+            return Ok(true);
+        }
+
+        let mut config = v2_line_config(&self.flags, &[]);
+        config.flags |= ffi::GPIO_V2_LINE_FLAG_EDGE_RISING | ffi::GPIO_V2_LINE_FLAG_EDGE_FALLING;
+        ffi::gpio_v2_line_set_config_ioctl(self.file.as_raw_fd(), &mut config)?;
+        let res = self.wait_for_value(value, timeout);
+        let mut config = v2_line_config(&self.flags, &[]);
+        let restored = ffi::gpio_v2_line_set_config_ioctl(self.file.as_raw_fd(), &mut config);
+        let reached = res?;
+        restored?;
+        Ok(reached)
+    }
+
+    /// Wait for `value` with edge detection enabled, see `wait_until()`
+    fn wait_for_value(&self, value: u8, timeout: Option<Duration>) -> Result<bool> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            // Reading the value after enabling edges catches a change that
+            // happened before
+            if self.get_value()? == value {
+                return Ok(true);
+            }
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if !poll_readable(self.file.as_raw_fd(), timeout_ms(remaining))? {
+                return Ok(false);
+            }
+            // Only the level matters, discard the events
+            while poll_readable(self.file.as_raw_fd(), 0)? {
+                if read_struct::<ffi::gpio_v2_line_event>(&self.file)?.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Request whether the line is active
+    ///
+    /// This is [`get_value`] as a `bool`, `true` meaning active.
+    ///
+    /// [`get_value`]: struct.LineHandle.html#method.get_value
+    pub fn get_bool(&self) -> Result<bool> {
+        Ok(self.get_value()? != 0)
+    }
+
+    /// Request that the line be driven active (`true`) or inactive
+    ///
+    /// This is [`set_value`] taking a `bool`.
+    ///
+    /// [`set_value`]: struct.LineHandle.html#method.set_value
+    pub fn set_bool(&self, active: bool) -> Result<()> {
+        self.set_value(u8::from(active))
+    }
+
+    /// Change whether the line is active low without releasing it
+    ///
+    /// The line is reconfigured in place, so unlike requesting it again
+    /// there is no window in which another process could take it.  For
+    /// outputs the electrical level is kept, which means the logical value
+    /// returned by [`get_value`] is inverted.  [`flags`] reflects the new
+    /// configuration afterwards.
+    ///
+    /// # Errors
+    ///
+    /// This requires a line requested through the v2 uAPI; for lines
+    /// requested through the v1 uAPI (on kernels before 5.10) an
+    /// [`ErrorKind::RequiresV2`] error is returned.
+    ///
+    /// [`get_value`]: struct.LineHandle.html#method.get_value
+    /// [`flags`]: struct.LineHandle.html#method.flags
+    /// [`ErrorKind::RequiresV2`]: errors/enum.ErrorKind.html#variant.RequiresV2
+    pub fn set_active_low(&mut self, active_low: bool) -> Result<()> {
+        if self.abi != Abi::V2 {
+            return Err(requires_v2_err());
+        }
+        let mut flags = self.flags.clone();
+        flags.set(LineRequestFlags::ACTIVE_LOW, active_low);
+        if flags == self.flags {
+            return Ok(());
+        }
+        let value = if flags.contains(LineRequestFlags::OUTPUT) {
+            self.get_value()? ^ 1
+        } else {
+            0
+        };
+        let mut config = v2_line_config(&flags, &[value]);
+        ffi::gpio_v2_line_set_config_ioctl(self.file.as_raw_fd(), &mut config)?;
+        self.flags = flags;
+        Ok(())
+    }
+
+    /// Drive the line through a sequence of values
+    ///
+    /// Each entry of `pattern` sets the line to a value and then holds it
+    /// for the given duration, blocking the calling thread until the whole
+    /// pattern has been output.  The line keeps the last value afterwards.
+    /// An empty pattern does nothing; an entry with a zero duration still
+    /// sets its value.
+    ///
+    /// With the `async-tokio` feature, [`drive_pattern_async`] does the
+    /// same without blocking the executor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineRequestFlags};
+    /// use std::time::Duration;
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let led = chip.get_line(4)?.request(LineRequestFlags::OUTPUT, 0, "status-led")?;
+    /// let short = Duration::from_millis(100);
+    /// let long = Duration::from_millis(300);
+    /// // "A" in Morse code
+    /// led.drive_pattern(&[(1, short), (0, short), (1, long), (0, long)])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`drive_pattern_async`]: struct.LineHandle.html#method.drive_pattern_async
+    pub fn drive_pattern(&self, pattern: &[(u8, Duration)]) -> Result<()> {
+        for &(value, duration) in pattern {
+            self.set_value(value)?;
+            std::thread::sleep(duration);
+        }
         Ok(())
     }
 
+    /// Read the value of the line together with how it was requested
+    ///
+    /// Only the value is requested from the kernel; the direction and
+    /// polarity are those the handle was requested with, so no extra
+    /// [`Line::info`] call on the chip is needed.
+    ///
+    /// [`Line::info`]: struct.Line.html#method.info
+    pub fn read(&self) -> Result<LineState> {
+        Ok(LineState {
+            value: self.get_value()?,
+            direction: if self.flags.contains(LineRequestFlags::OUTPUT) {
+                LineDirection::Out
+            } else {
+                LineDirection::In
+            },
+            active_low: self.flags.contains(LineRequestFlags::ACTIVE_LOW),
+        })
+    }
+
+    /// Release the line back to the kernel
+    ///
+    /// Dropping the handle releases the line as well, but ignores any
+    /// error from closing the file descriptor.  This reports it instead,
+    /// so once this returns `Ok` the line is known to be free to be
+    /// requested again, e.g. with different flags.
+    pub fn release(self) -> Result<()> {
+        close_file(self.file)
+    }
+
     /// Get the Line information associated with this handle.
     pub fn line(&self) -> &Line {
         &self.line
     }
 
     /// Get the flags with which this handle was created
+    ///
+    /// This includes changes made with [`set_active_low`].
+    ///
+    /// [`set_active_low`]: struct.LineHandle.html#method.set_active_low
     pub fn flags(&self) -> LineRequestFlags {
         self.flags.clone()
     }
+
+    /// Get the consumer label the kernel stored for the line
+    ///
+    /// This reads back the line information from the chip (see
+    /// [`Line::info`]), so it shows the label as actually stored,
+    /// including any truncation.  Returns `None` if the line was requested
+    /// with an empty consumer.
+    ///
+    /// [`Line::info`]: struct.Line.html#method.info
+    pub fn consumer(&self) -> Result<Option<String>> {
+        let info = self.line.info()?;
+        Ok(info.consumer().map(String::from))
+    }
+
+    /// Get the current configuration of the line
+    ///
+    /// This reflects changes made through this handle, e.g. with
+    /// [`set_active_low`].  The kernel has no ioctl to query the
+    /// configuration through the line request itself, so this queries the
+    /// line info from the chip the handle was requested from, like
+    /// [`Line::info`]; no separate reference to the chip is needed.  It is
+    /// the same query as [`consumer`], returning the whole line info.
+    ///
+    /// [`set_active_low`]: struct.LineHandle.html#method.set_active_low
+    /// [`Line::info`]: struct.Line.html#method.info
+    /// [`consumer`]: struct.LineHandle.html#method.consumer
+    pub fn get_config(&self) -> Result<LineInfo> {
+        self.line.info()
+    }
+
+    /// Iterate over the value of the line sampled every `period`
+    ///
+    /// Each item holds the time the value was read along with the value.
+    /// The first value is read one `period` after the iterator is first
+    /// polled, and the iterator never ends.  Samples are taken at a fixed
+    /// rate: the time spent outside of the iterator between samples is
+    /// subtracted from the next wait, and if it exceeds `period` the next
+    /// value is read right away.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineRequestFlags};
+    /// use std::time::Duration;
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let input = chip.get_line(4)?.request(LineRequestFlags::INPUT, 0, "logger")?;
+    /// // Log the level at 100 Hz for one second
+    /// for sample in input.sample(Duration::from_millis(10)).take(100) {
+    ///     let (time, value) = sample?;
+    ///     println!("{:?}: {}", time, value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sample(&self, period: Duration) -> Samples<'_> {
+        Samples {
+            handle: self,
+            timer: SampleTimer::new(period),
+        }
+    }
 }
 
-impl AsRawFd for LineHandle {
-    /// Gets the raw file descriptor for the `LineHandle`.
-    fn as_raw_fd(&self) -> RawFd {
-        self.file.as_raw_fd()
+/// Paces sampling at a fixed rate
+#[derive(Debug)]
+struct SampleTimer {
+    period: Duration,
+    next: Option<Instant>,
+}
+
+impl SampleTimer {
+    fn new(period: Duration) -> Self {
+        SampleTimer { period, next: None }
+    }
+
+    /// Sleep until the next sample is due
+    fn wait(&mut self) {
+        let now = Instant::now();
+        let due = self.next.unwrap_or(now + self.period);
+        if due > now {
+            std::thread::sleep(due - now);
+        }
+        // Don't try to catch up on samples missed while not polled
+        self.next = Some(due.max(now) + self.period);
     }
 }
 
-/// A collection of lines that can be accesses simultaneously
+/// Iterator over the sampled value of a line
 ///
-/// This is a collection of lines, all from the same GPIO chip that can
-/// all be accessed simultaneously
+/// Created by [`LineHandle::sample`].
+///
+/// [`LineHandle::sample`]: struct.LineHandle.html#method.sample
 #[derive(Debug)]
-pub struct Lines {
-    lines: Vec<Line>,
+pub struct Samples<'a> {
+    handle: &'a LineHandle,
+    timer: SampleTimer,
 }
 
-impl Lines {
-    fn new(chip: Arc<InnerChip>, offsets: &[u32]) -> Result<Self> {
-        let res: Result<Vec<Line>> = offsets
-            .iter()
-            .map(|off| Line::new(chip.clone(), *off))
-            .collect();
-        let lines = res?;
-        Ok(Self { lines })
+impl Iterator for Samples<'_> {
+    type Item = Result<(Instant, u8)>;
+
+    fn next(&mut self) -> Option<Result<(Instant, u8)>> {
+        self.timer.wait();
+        let time = Instant::now();
+        Some(self.handle.get_value().map(|value| (time, value)))
     }
+}
 
-    /// Get a handle to the parent chip for the lines
-    pub fn chip(&self) -> Chip {
-        self.lines[0].chip()
+/// The value of a requested line along with its configuration
+///
+/// Returned by [`LineHandle::read`].
+///
+/// [`LineHandle::read`]: struct.LineHandle.html#method.read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineState {
+    value: u8,
+    direction: LineDirection,
+    active_low: bool,
+}
+
+impl LineState {
+    /// The (logical) value of the line; see [`LineHandle::get_value`]
+    ///
+    /// [`LineHandle::get_value`]: struct.LineHandle.html#method.get_value
+    pub fn value(&self) -> u8 {
+        self.value
     }
 
-    /// Get the number of lines in the collection
-    pub fn is_empty(&self) -> bool {
-        self.lines.is_empty()
+    /// The direction the line was requested with
+    pub fn direction(&self) -> LineDirection {
+        self.direction
     }
 
-    /// Get the number of lines in the collection
+    /// True if the line was requested as active low
+    pub fn is_active_low(&self) -> bool {
+        self.active_low
+    }
+}
+
+/// Handle for a line requested as an input
+///
+/// Obtained from [`Line::request_input`].  It only allows reading the value
+/// of the line.
+///
+/// [`Line::request_input`]: struct.Line.html#method.request_input
+#[derive(Debug)]
+pub struct InputLineHandle(LineHandle);
+
+impl InputLineHandle {
+    /// Request the current state of this Line from the kernel
+    ///
+    /// See [`LineHandle::get_value`].
+    ///
+    /// [`LineHandle::get_value`]: struct.LineHandle.html#method.get_value
+    pub fn get_value(&self) -> Result<u8> {
+        self.0.get_value()
+    }
+
+    /// Get the Line information associated with this handle.
+    pub fn line(&self) -> &Line {
+        self.0.line()
+    }
+
+    /// Get the flags with which this handle was created
+    pub fn flags(&self) -> LineRequestFlags {
+        self.0.flags()
+    }
+}
+
+impl AsRawFd for InputLineHandle {
+    /// Gets the raw file descriptor for the `InputLineHandle`.
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl AsFd for InputLineHandle {
+    /// Gets the file descriptor for the `InputLineHandle`.
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+/// Handle for a line requested as an output
+///
+/// Obtained from [`Line::request_output`].
+///
+/// [`Line::request_output`]: struct.Line.html#method.request_output
+#[derive(Debug)]
+pub struct OutputLineHandle(LineHandle);
+
+impl OutputLineHandle {
+    /// Request the current state of this Line from the kernel
+    ///
+    /// See [`LineHandle::get_value`].
+    ///
+    /// [`LineHandle::get_value`]: struct.LineHandle.html#method.get_value
+    pub fn get_value(&self) -> Result<u8> {
+        self.0.get_value()
+    }
+
+    /// Request that the line be driven to the specified value
+    ///
+    /// See [`LineHandle::set_value`].
+    ///
+    /// [`LineHandle::set_value`]: struct.LineHandle.html#method.set_value
+    pub fn set_value(&self, value: u8) -> Result<()> {
+        self.0.set_value(value)
+    }
+
+    /// Get the Line information associated with this handle.
+    pub fn line(&self) -> &Line {
+        self.0.line()
+    }
+
+    /// Get the flags with which this handle was created
+    pub fn flags(&self) -> LineRequestFlags {
+        self.0.flags()
+    }
+}
+
+impl AsRawFd for OutputLineHandle {
+    /// Gets the raw file descriptor for the `OutputLineHandle`.
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl AsFd for OutputLineHandle {
+    /// Gets the file descriptor for the `OutputLineHandle`.
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl AsRawFd for LineHandle {
+    /// Gets the raw file descriptor for the `LineHandle`.
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl AsFd for LineHandle {
+    /// Gets the file descriptor for the `LineHandle`.
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+impl IntoRawFd for LineHandle {
+    /// Gives up the handle without releasing the line
+    ///
+    /// The caller takes over the file descriptor; the line stays requested
+    /// until it is closed.  See [`LineHandle::from_raw_fd`] to rebuild a
+    /// handle from it.
+    ///
+    /// [`LineHandle::from_raw_fd`]: struct.LineHandle.html#method.from_raw_fd
+    fn into_raw_fd(self) -> RawFd {
+        self.file.into_raw_fd()
+    }
+}
+
+/// A collection of lines that can be accesses simultaneously
+///
+/// This is a collection of lines, all from the same GPIO chip that can
+/// all be accessed simultaneously
+#[derive(Debug)]
+pub struct Lines {
+    lines: Vec<Line>,
+}
+
+impl Lines {
+    fn new(chip: Arc<InnerChip>, offsets: &[u32]) -> Result<Self> {
+        if offsets.len() > ffi::GPIOHANDLES_MAX {
+            return Err(too_many_lines_err(offsets.len()));
+        }
+        let res: Result<Vec<Line>> = offsets
+            .iter()
+            .map(|off| Line::new(chip.clone(), *off))
+            .collect();
+        let lines = res?;
+        Ok(Self { lines })
+    }
+
+    /// Get a handle to the parent chip for the lines
+    pub fn chip(&self) -> Chip {
+        self.lines[0].chip()
+    }
+
+    /// Get the number of lines in the collection
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Get the number of lines in the collection
     pub fn len(&self) -> usize {
         self.lines.len()
     }
 
+    /// Get an iterator over the lines in the collection
+    pub fn iter(&self) -> slice::Iter<'_, Line> {
+        self.lines.iter()
+    }
+
     /// Request access to interact with these lines from the kernel
     ///
     /// This is similar to the "export" operation present in the sysfs
@@ -750,7 +3044,9 @@ impl Lines {
     /// For an output, the `default` parameter specifies the value
     /// each line should have when it is configured as an output.  The
     /// `consumer` string should describe the process consuming the
-    /// line (this will be truncated to 31 characters if too long).
+    /// line (this will be truncated to 31 bytes if too long, without
+    /// splitting a UTF-8 character).  An empty `consumer` leaves the
+    /// line without a label.
     ///
     /// # Errors
     ///
@@ -762,9 +3058,26 @@ impl Lines {
     /// already in use.  One can check for this prior to making the
     /// request using [`is_kernel`].
     ///
+    /// Flags selecting both `OPEN_DRAIN` and `OPEN_SOURCE` are rejected
+    /// with [`ErrorKind::ConflictingFlags`] before the kernel is asked, as
+    /// are default values other than 0 or 1 with
+    /// [`ErrorKind::InvalidValue`], consumers containing a NUL character
+    /// with [`ErrorKind::InvalidConsumer`], and an empty group of lines
+    /// with [`ErrorKind::InvalidRequest`].
+    ///
+    /// The lines are requested through the v2 uAPI if the kernel supports
+    /// it (see [`Chip::supports_v2`]), which enables
+    /// [`MultiLineHandle::set_values_masked`].
+    ///
     /// [`Error`]: errors/struct.Error.html
     /// [`ErrorKind::Ioctl`]: errors/enum.ErrorKind.html#variant.Ioctl
+    /// [`ErrorKind::ConflictingFlags`]: errors/enum.ErrorKind.html#variant.ConflictingFlags
+    /// [`ErrorKind::InvalidValue`]: errors/enum.ErrorKind.html#variant.InvalidValue
+    /// [`ErrorKind::InvalidRequest`]: errors/enum.ErrorKind.html#variant.InvalidRequest
+    /// [`ErrorKind::InvalidConsumer`]: errors/enum.ErrorKind.html#variant.InvalidConsumer
     /// [`is_kernel`]: struct.Line.html#method.is_kernel
+    /// [`Chip::supports_v2`]: struct.Chip.html#method.supports_v2
+    /// [`MultiLineHandle::set_values_masked`]: struct.MultiLineHandle.html#method.set_values_masked
     pub fn request(
         &self,
         flags: LineRequestFlags,
@@ -772,9 +3085,28 @@ impl Lines {
         consumer: &str,
     ) -> Result<MultiLineHandle> {
         let n = self.lines.len();
+        if n == 0 {
+            return Err(invalid_err(0, 0));
+        }
         if default.len() != n {
             return Err(invalid_err(n, default.len()));
         }
+        flags.validate()?;
+        for &value in default {
+            validate_value(value)?;
+        }
+        let consumer = consumer_label(consumer)?;
+        if self.lines[0].chip.supports_v2() {
+            let config = v2_line_config(&flags, default);
+            let file = v2_line_request(&self.lines, config, 0, consumer)?;
+            let lines = self.lines.clone();
+            return Ok(MultiLineHandle {
+                lines: Self { lines },
+                flags: vec![flags; n],
+                file,
+                abi: Abi::V2,
+            });
+        }
         // prepare the request; the kernel consumes some of these values and will
         // set the fd for us.
         let mut request = ffi::gpiohandle_request {
@@ -791,86 +3123,725 @@ impl Lines {
             request.default_values[i] = default[i];
         }
         unsafe {
-            rstr_lcpy(
+            bytes_lcpy(
                 request.consumer_label[..].as_mut_ptr(),
                 consumer,
                 request.consumer_label.len(),
             );
         }
-        ffi::gpio_get_linehandle_ioctl(self.lines[0].chip().inner.file.as_raw_fd(), &mut request)?;
+        ffi::gpio_get_linehandle_ioctl(self.lines[0].chip().inner.file.as_raw_fd(), &mut request)
+            .map_err(|e| map_busy(&self.lines, e))?;
         let lines = self.lines.clone();
         Ok(MultiLineHandle {
             lines: Self { lines },
+            flags: vec![flags; n],
             file: unsafe { File::from_raw_fd(request.fd) },
+            abi: Abi::V1,
         })
     }
-}
 
-impl Index<usize> for Lines {
-    type Output = Line;
-
-    fn index(&self, i: usize) -> &Line {
-        &self.lines[i]
+    /// Request the lines with a common default value and a few exceptions
+    ///
+    /// This is [`request`] with all default values set to `base`, except
+    /// for the lines given by index in `overrides`.  It saves building the
+    /// full list of defaults for wide groups of outputs in which only a
+    /// few lines differ.
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineRequestFlags};
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let lines = chip.get_lines(&[8, 9, 10, 11, 12, 13, 14, 15])?;
+    /// // All low except the chip selects at indices 2 and 5
+    /// let handle = lines.request_with_default_overrides(
+    ///     LineRequestFlags::OUTPUT,
+    ///     0,
+    ///     &[(2, 1), (5, 1)],
+    ///     "bus",
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An index which is not less than the number of lines results in an
+    /// [`ErrorKind::Index`] error before the kernel is asked.  The other
+    /// errors are those of [`request`].
+    ///
+    /// [`request`]: struct.Lines.html#method.request
+    /// [`ErrorKind::Index`]: errors/enum.ErrorKind.html#variant.Index
+    pub fn request_with_default_overrides(
+        &self,
+        flags: LineRequestFlags,
+        base: u8,
+        overrides: &[(usize, u8)],
+        consumer: &str,
+    ) -> Result<MultiLineHandle> {
+        let n = self.lines.len();
+        let mut default = vec![base; n];
+        for &(index, value) in overrides {
+            match default.get_mut(index) {
+                Some(slot) => *slot = value,
+                None => return Err(index_err(index, n)),
+            }
+        }
+        self.request(flags, &default, consumer)
     }
-}
-
-/// Handle for interacting with a "requested" line
-///
-/// In order for userspace to read/write the value of a GPIO
-/// it must be requested from the chip using [`Line::request`].
-/// On success, the kernel creates an anonymous file descriptor
-/// for interacting with the requested line.  This structure
-/// is the go-between for callers and that file descriptor.
-///
-/// [`Line::request`]: struct.Line.html#method.request
-#[derive(Debug)]
-pub struct MultiLineHandle {
-    lines: Lines,
-    file: File,
-}
 
-impl MultiLineHandle {
-    /// Request the current state of this Line from the kernel
+    /// Request the lines and check that their default values were applied
     ///
-    /// This call is expected to succeed for both input and output
-    /// lines.  It should be noted, however, that some drivers may
-    /// not be able to give any useful information when the value
-    /// is requested for an output line.
+    /// This is like [`request`], but for outputs the values of the lines
+    /// are read back after the request.  If any of them does not match
+    /// `default` the lines are released and an
+    /// [`ErrorKind::DefaultsNotApplied`] error is returned, which helps
+    /// catching drivers ignoring the default values.  For inputs this is
+    /// the same as [`request`].
     ///
-    /// This value should be 0 or 1 which a "1" representing that
-    /// the line is active.  Usually this means that the line is
-    /// at logic-level high but it could mean the opposite if the
-    /// line has been marked as being `ACTIVE_LOW`.
-    pub fn get_values(&self) -> Result<Vec<u8>> {
-        let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
-        ffi::gpiohandle_get_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
-        let n = self.num_lines();
-        let values: Vec<u8> = (0..n).map(|i| data.values[i]).collect();
-        Ok(values)
+    /// Note that not all drivers report the value of an output
+    /// accurately; with such drivers this check may fail even though the
+    /// lines are driven correctly.
+    ///
+    /// [`request`]: struct.Lines.html#method.request
+    /// [`ErrorKind::DefaultsNotApplied`]: errors/enum.ErrorKind.html#variant.DefaultsNotApplied
+    pub fn request_verified(
+        &self,
+        flags: LineRequestFlags,
+        default: &[u8],
+        consumer: &str,
+    ) -> Result<MultiLineHandle> {
+        let verify = flags.contains(LineRequestFlags::OUTPUT);
+        let handle = self.request(flags, default, consumer)?;
+        if verify {
+            let values = handle.get_values()?;
+            if values
+                .iter()
+                .zip(default)
+                .any(|(&value, &default)| (value != 0) != (default != 0))
+            {
+                return Err(defaults_not_applied_err(default, values));
+            }
+        }
+        Ok(handle)
     }
 
-    /// Request that the line be driven to the specified value
+    /// Request these lines with different flags for each line
     ///
-    /// The value should be 0 or 1 with 1 representing a request
-    /// to make the line "active".  Usually "active" means
-    /// logic level high unless the line has been marked as `ACTIVE_LOW`.
+    /// This is like [`request`], but `flags[i]` configures line `i`, e.g.
+    /// to mark only some outputs as `ACTIVE_LOW`.  Values passed to
+    /// [`MultiLineHandle::set_values`] are then logical values for each
+    /// line: setting all lines to 1 makes each of them active, whatever
+    /// its polarity.
     ///
-    /// Calling `set_value` on a line that is not an output will
-    /// likely result in an error (from the kernel).
-    pub fn set_values(&self, values: &[u8]) -> Result<()> {
-        let n = self.num_lines();
-        if values.len() != n {
-            return Err(invalid_err(n, values.len()));
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineRequestFlags};
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let out = LineRequestFlags::OUTPUT;
+    /// let out_low = LineRequestFlags::OUTPUT | LineRequestFlags::ACTIVE_LOW;
+    /// let handle = chip.get_lines(&[4, 5, 6])?.request_with_configs(
+    ///     &[out.clone(), out_low, out],
+    ///     &[0, 0, 0],
+    ///     "harness",
+    /// )?;
+    /// // Switch all three loads on
+    /// handle.set_values(&[1, 1, 1])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Lines with different flags can only be requested together through
+    /// the v2 uAPI; on older kernels (see [`Chip::supports_v2`]) this
+    /// results in an [`ErrorKind::RequiresV2`] error unless all flags are
+    /// the same.  The v2 uAPI can hold at most 10 distinct configurations
+    /// per request (one of them taken by the default values of outputs);
+    /// more result in an [`ErrorKind::TooManyConfigs`] error.  The other
+    /// errors are those of [`request`].
+    ///
+    /// [`request`]: struct.Lines.html#method.request
+    /// [`MultiLineHandle::set_values`]: struct.MultiLineHandle.html#method.set_values
+    /// [`Chip::supports_v2`]: struct.Chip.html#method.supports_v2
+    /// [`ErrorKind::RequiresV2`]: errors/enum.ErrorKind.html#variant.RequiresV2
+    /// [`ErrorKind::TooManyConfigs`]: errors/enum.ErrorKind.html#variant.TooManyConfigs
+    pub fn request_with_configs(
+        &self,
+        flags: &[LineRequestFlags],
+        default: &[u8],
+        consumer: &str,
+    ) -> Result<MultiLineHandle> {
+        let n = self.lines.len();
+        if n == 0 {
+            return Err(invalid_err(0, 0));
         }
-        let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
-        data.values[..n].clone_from_slice(&values[..n]);
-        ffi::gpiohandle_set_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
-        Ok(())
+        if flags.len() != n {
+            return Err(invalid_err(n, flags.len()));
+        }
+        if flags.iter().all(|f| *f == flags[0]) {
+            return self.request(flags[0].clone(), default, consumer);
+        }
+        if default.len() != n {
+            return Err(invalid_err(n, default.len()));
+        }
+        for line_flags in flags {
+            line_flags.validate()?;
+        }
+        for &value in default {
+            validate_value(value)?;
+        }
+        if !self.lines[0].chip.supports_v2() {
+            return Err(requires_v2_err());
+        }
+        let config = v2_line_configs(flags, default)?;
+        let consumer = consumer_label(consumer)?;
+        let file = v2_line_request(&self.lines, config, 0, consumer)?;
+        let lines = self.lines.clone();
+        Ok(MultiLineHandle {
+            lines: Self { lines },
+            flags: flags.to_vec(),
+            file,
+            abi: Abi::V2,
+        })
     }
 
-    /// Get the number of lines associated with this handle
-    pub fn num_lines(&self) -> usize {
-        self.lines.len()
+    /// Get a single event handle for all of these lines
+    ///
+    /// This is like [`Line::events`], but all lines share one kernel
+    /// request and one file descriptor.  Events of the lines are delivered
+    /// in the order they occurred and carry the offset of the line which
+    /// generated them (see [`LineEvent::offset`]).  This is more efficient
+    /// than one event handle per line when watching many lines.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, EventRequestFlags, LineRequestFlags};
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let lines = chip.get_lines(&[4, 5, 6])?;
+    ///
+    /// for event in lines.events(
+    ///     LineRequestFlags::INPUT,
+    ///     EventRequestFlags::BOTH_EDGES,
+    ///     "rust-gpio",
+    /// )? {
+    ///     let event = event?;
+    ///     println!("{:?}: {:?}", event.offset(), event.event_type());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The v1 uAPI has no such request, so kernels without v2 support
+    /// (see [`Chip::supports_v2`]) result in an [`ErrorKind::RequiresV2`]
+    /// error.  An empty group of lines results in an
+    /// [`ErrorKind::InvalidRequest`] error.
+    ///
+    /// [`ErrorKind::InvalidRequest`]: errors/enum.ErrorKind.html#variant.InvalidRequest
+    /// [`Line::events`]: struct.Line.html#method.events
+    /// [`LineEvent::offset`]: struct.LineEvent.html#method.offset
+    /// [`Chip::supports_v2`]: struct.Chip.html#method.supports_v2
+    /// [`ErrorKind::RequiresV2`]: errors/enum.ErrorKind.html#variant.RequiresV2
+    pub fn events(
+        &self,
+        handle_flags: LineRequestFlags,
+        event_flags: EventRequestFlags,
+        consumer: &str,
+    ) -> Result<MultiLineEventHandle> {
+        if self.lines.is_empty() {
+            return Err(invalid_err(0, 0));
+        }
+        handle_flags.validate_events()?;
+        event_flags.validate()?;
+        if !self.lines[0].chip.supports_v2() {
+            return Err(requires_v2_err());
+        }
+        let config = EventConfig::new(handle_flags, event_flags).v2_config(self.lines.len());
+        let consumer = consumer_label(consumer)?;
+        let file = v2_line_request(&self.lines, config, 0, consumer)?;
+        let lines = self.lines.clone();
+        Ok(MultiLineEventHandle {
+            lines: Self { lines },
+            file,
+        })
+    }
+}
+
+/// Request `lines` (all of the same chip) through the v2 uAPI
+///
+/// An `event_buffer_size` of 0 selects the default size of the kernel.
+fn v2_line_request(
+    lines: &[Line],
+    config: ffi::gpio_v2_line_config,
+    event_buffer_size: u32,
+    consumer: &[u8],
+) -> Result<File> {
+    let mut request: ffi::gpio_v2_line_request = unsafe { mem::zeroed() };
+    for (offset, line) in request.offsets.iter_mut().zip(lines) {
+        *offset = line.offset();
+    }
+    request.num_lines = lines.len() as u32;
+    request.config = config;
+    request.event_buffer_size = event_buffer_size;
+    unsafe {
+        bytes_lcpy(
+            request.consumer[..].as_mut_ptr(),
+            consumer,
+            request.consumer.len(),
+        );
+    }
+    ffi::gpio_v2_get_line_ioctl(lines[0].chip.file.as_raw_fd(), &mut request)
+        .map_err(|e| map_busy(lines, e))?;
+    Ok(unsafe { File::from_raw_fd(request.fd) })
+}
+
+/// Build the uAPI v2 configuration for lines requested with `flags`
+///
+/// For outputs, `values` holds the value to drive each line to.
+fn v2_line_config(flags: &LineRequestFlags, values: &[u8]) -> ffi::gpio_v2_line_config {
+    let mut config: ffi::gpio_v2_line_config = unsafe { mem::zeroed() };
+    config.flags = flags.v2_flags();
+    if flags.contains(LineRequestFlags::OUTPUT) {
+        let attr = &mut config.attrs[0];
+        attr.attr.id = ffi::GPIO_V2_LINE_ATTR_ID_OUTPUT_VALUES;
+        attr.attr.value.values = values_to_bits(values);
+        attr.mask = line_mask(values.len());
+        config.num_attrs = 1;
+    }
+    config
+}
+
+/// Build the uAPI v2 configuration for lines requested with `flags[i]` each
+///
+/// The flags of the first line are the default; the lines with other
+/// flags get an attribute per distinct set of flags.  For outputs,
+/// `values` holds the value to drive each line to.
+fn v2_line_configs(flags: &[LineRequestFlags], values: &[u8]) -> Result<ffi::gpio_v2_line_config> {
+    let mut config: ffi::gpio_v2_line_config = unsafe { mem::zeroed() };
+    config.flags = flags[0].v2_flags();
+
+    let mut groups: Vec<(u64, u64)> = Vec::new();
+    let mut outputs = 0;
+    for (i, line_flags) in flags.iter().enumerate() {
+        let bit = 1 << i;
+        if line_flags.contains(LineRequestFlags::OUTPUT) {
+            outputs |= bit;
+        }
+        let v2_flags = line_flags.v2_flags();
+        if v2_flags == config.flags {
+            continue;
+        }
+        match groups
+            .iter_mut()
+            .find(|(group_flags, _)| *group_flags == v2_flags)
+        {
+            Some((_, mask)) => *mask |= bit,
+            None => groups.push((v2_flags, bit)),
+        }
+    }
+
+    let num_attrs = groups.len() + usize::from(outputs != 0);
+    if num_attrs > ffi::GPIO_V2_LINE_NUM_ATTRS_MAX {
+        return Err(too_many_configs_err(num_attrs));
+    }
+    for ((v2_flags, mask), attr) in groups.into_iter().zip(config.attrs.iter_mut()) {
+        attr.attr.id = ffi::GPIO_V2_LINE_ATTR_ID_FLAGS;
+        attr.attr.value.flags = v2_flags;
+        attr.mask = mask;
+    }
+    if outputs != 0 {
+        let attr = &mut config.attrs[num_attrs - 1];
+        attr.attr.id = ffi::GPIO_V2_LINE_ATTR_ID_OUTPUT_VALUES;
+        attr.attr.value.values = values_to_bits(values) & outputs;
+        attr.mask = outputs;
+    }
+    config.num_attrs = num_attrs as u32;
+    Ok(config)
+}
+
+/// Turn an `EBUSY` error requesting `lines` into an `ErrorKind::Busy` error
+///
+/// The consumer is that of the first of the lines which is in use.
+fn map_busy(lines: &[Line], err: Error) -> Error {
+    if err.ioctl_errno() != Some(nix::errno::Errno::EBUSY) {
+        return err;
+    }
+    let consumer = lines
+        .iter()
+        .filter_map(|line| line.info().ok())
+        .find(|info| info.is_used())
+        .and_then(|info| info.consumer().map(String::from));
+    busy_err(consumer)
+}
+
+/// Reject line values other than 0 and 1
+pub(crate) fn validate_value(value: u8) -> Result<()> {
+    match value {
+        0 | 1 => Ok(()),
+        _ => Err(invalid_value_err(value)),
+    }
+}
+
+/// Bitmap with the lowest `n` bits set
+fn line_mask(n: usize) -> u64 {
+    if n >= 64 {
+        u64::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Pack one value per line into a bitmap, bit `i` holding `values[i]`
+fn values_to_bits(values: &[u8]) -> u64 {
+    values
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, &value)| bits | (u64::from(value != 0) << i))
+}
+
+impl Index<usize> for Lines {
+    type Output = Line;
+
+    fn index(&self, i: usize) -> &Line {
+        &self.lines[i]
+    }
+}
+
+impl<'a> IntoIterator for &'a Lines {
+    type Item = &'a Line;
+    type IntoIter = slice::Iter<'a, Line>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Handle for interacting with a "requested" line
+///
+/// In order for userspace to read/write the value of a GPIO
+/// it must be requested from the chip using [`Line::request`].
+/// On success, the kernel creates an anonymous file descriptor
+/// for interacting with the requested line.  This structure
+/// is the go-between for callers and that file descriptor.
+///
+/// [`Line::request`]: struct.Line.html#method.request
+pub struct MultiLineHandle {
+    lines: Lines,
+    /// The flags of each line, in the order of `lines`
+    flags: Vec<LineRequestFlags>,
+    file: File,
+    abi: Abi,
+}
+
+impl std::fmt::Debug for MultiLineHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let offsets: Vec<u32> = self.lines.iter().map(Line::offset).collect();
+        let mut s = f.debug_struct("MultiLineHandle");
+        if let Some(line) = self.lines.lines.first() {
+            s.field("chip", &line.chip.name);
+        }
+        s.field("offsets", &offsets);
+        // Only list the flags per line if they differ
+        match self.flags.first() {
+            Some(first) if self.flags.iter().all(|flags| flags == first) => s.field("flags", first),
+            _ => s.field("flags", &self.flags),
+        };
+        s.finish()
+    }
+}
+
+impl MultiLineHandle {
+    /// Request the current state of this Line from the kernel
+    ///
+    /// This call is expected to succeed for both input and output
+    /// lines.  It should be noted, however, that some drivers may
+    /// not be able to give any useful information when the value
+    /// is requested for an output line.
+    ///
+    /// This value should be 0 or 1 which a "1" representing that
+    /// the line is active.  Usually this means that the line is
+    /// at logic-level high but it could mean the opposite if the
+    /// line has been marked as being `ACTIVE_LOW`.
+    pub fn get_values(&self) -> Result<Vec<u8>> {
+        let n = self.num_lines();
+        match self.abi {
+            Abi::V1 => {
+                let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
+                ffi::gpiohandle_get_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+                let values: Vec<u8> = (0..n).map(|i| data.values[i]).collect();
+                Ok(values)
+            }
+            Abi::V2 => {
+                let mut data = ffi::gpio_v2_line_values {
+                    bits: 0,
+                    mask: line_mask(n),
+                };
+                ffi::gpio_v2_line_get_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+                Ok((0..n).map(|i| ((data.bits >> i) & 1) as u8).collect())
+            }
+        }
+    }
+
+    /// Request that the line be driven to the specified value
+    ///
+    /// The value should be 0 or 1 with 1 representing a request
+    /// to make the line "active".  Usually "active" means
+    /// logic level high unless the line has been marked as `ACTIVE_LOW`.
+    ///
+    /// Calling `set_value` on a line that is not an output will
+    /// likely result in an error (from the kernel).  Values other than
+    /// 0 or 1 are rejected with [`ErrorKind::InvalidValue`].
+    ///
+    /// [`ErrorKind::InvalidValue`]: errors/enum.ErrorKind.html#variant.InvalidValue
+    pub fn set_values(&self, values: &[u8]) -> Result<()> {
+        let n = self.num_lines();
+        if values.len() != n {
+            return Err(invalid_err(n, values.len()));
+        }
+        for &value in values {
+            validate_value(value)?;
+        }
+        match self.abi {
+            Abi::V1 => {
+                let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
+                data.values[..n].clone_from_slice(&values[..n]);
+                ffi::gpiohandle_set_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+            }
+            Abi::V2 => {
+                let mut data = ffi::gpio_v2_line_values {
+                    bits: values_to_bits(values),
+                    mask: line_mask(n),
+                };
+                ffi::gpio_v2_line_set_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drive all lines to the same value
+    ///
+    /// This is [`set_values`] with `value` for every line, e.g. to put all
+    /// outputs into a safe state with a single call.  Values other than 0
+    /// or 1 are rejected with [`ErrorKind::InvalidValue`].
+    ///
+    /// [`set_values`]: struct.MultiLineHandle.html#method.set_values
+    /// [`ErrorKind::InvalidValue`]: errors/enum.ErrorKind.html#variant.InvalidValue
+    pub fn set_all(&self, value: u8) -> Result<()> {
+        validate_value(value)?;
+        let n = self.num_lines();
+        match self.abi {
+            Abi::V1 => {
+                let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
+                data.values[..n].fill(value);
+                ffi::gpiohandle_set_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+            }
+            Abi::V2 => {
+                let mask = line_mask(n);
+                let mut data = ffi::gpio_v2_line_values {
+                    bits: if value == 1 { mask } else { 0 },
+                    mask,
+                };
+                ffi::gpio_v2_line_set_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Iterate over the values of the lines sampled every `period`
+    ///
+    /// This is [`LineHandle::sample`] for several lines, yielding the
+    /// values of all lines read at once.
+    ///
+    /// [`LineHandle::sample`]: struct.LineHandle.html#method.sample
+    pub fn sample(&self, period: Duration) -> MultiSamples<'_> {
+        MultiSamples {
+            handle: self,
+            timer: SampleTimer::new(period),
+        }
+    }
+
+    /// Request the current state of the lines, paired with their offsets
+    ///
+    /// This is [`get_values`] with each value paired with the offset of
+    /// its line, so callers do not need to keep track of the order in
+    /// which the lines were requested.
+    ///
+    /// [`get_values`]: struct.MultiLineHandle.html#method.get_values
+    pub fn get_values_map(&self) -> Result<Vec<(u32, u8)>> {
+        let values = self.get_values()?;
+        Ok(self.lines.iter().map(Line::offset).zip(values).collect())
+    }
+
+    /// Drive the lines to new values and return their previous values
+    ///
+    /// The current values are read immediately before the new ones are
+    /// set.  These are two separate calls, so a change made by another
+    /// process in between is not noticed, which is fine for a single user
+    /// of the lines.
+    pub fn swap_values(&self, values: &[u8]) -> Result<Vec<u8>> {
+        let n = self.num_lines();
+        if values.len() != n {
+            return Err(invalid_err(n, values.len()));
+        }
+        for &value in values {
+            validate_value(value)?;
+        }
+        let old = self.get_values()?;
+        self.set_values(values)?;
+        Ok(old)
+    }
+
+    /// Request whether each of the lines is active
+    ///
+    /// This is [`get_values`] as `bool`s, `true` meaning active.
+    ///
+    /// [`get_values`]: struct.MultiLineHandle.html#method.get_values
+    pub fn get_bools(&self) -> Result<Vec<bool>> {
+        Ok(self.get_values()?.iter().map(|&value| value != 0).collect())
+    }
+
+    /// Request that each line be driven active (`true`) or inactive
+    ///
+    /// This is [`set_values`] taking `bool`s.
+    ///
+    /// [`set_values`]: struct.MultiLineHandle.html#method.set_values
+    pub fn set_bools(&self, values: &[bool]) -> Result<()> {
+        let values: Vec<u8> = values.iter().map(|&value| u8::from(value)).collect();
+        self.set_values(&values)
+    }
+
+    /// Request the current state of the lines as a bitmap
+    ///
+    /// Bit `i` of the result holds the value of the `i`-th line of this
+    /// handle, in the order the lines were requested (which need not be
+    /// the order of their offsets).  Bits beyond [`num_lines`] are zero.
+    ///
+    /// [`num_lines`]: struct.MultiLineHandle.html#method.num_lines
+    pub fn get_values_bitmap(&self) -> Result<u64> {
+        let values = self.get_values()?;
+        Ok(values_to_bits(&values))
+    }
+
+    /// Request that the lines be driven to the values in a bitmap
+    ///
+    /// Bit `i` of `bits` is the value of the `i`-th line of this handle, in
+    /// the order the lines were requested.  Bits beyond [`num_lines`] are
+    /// ignored.
+    ///
+    /// [`num_lines`]: struct.MultiLineHandle.html#method.num_lines
+    pub fn set_values_bitmap(&self, bits: u64) -> Result<()> {
+        let values: Vec<u8> = (0..self.num_lines())
+            .map(|i| ((bits >> i) & 1) as u8)
+            .collect();
+        self.set_values(&values)
+    }
+
+    /// Atomically change the values of the lines selected by a mask
+    ///
+    /// Bit `i` of `mask` selects the `i`-th line of this handle, in the
+    /// order the lines were requested, and bit `i` of `bits` is its new
+    /// value.  All selected lines are changed with a single ioctl and the
+    /// other lines are left untouched by the kernel, so unlike
+    /// [`set_values_partial`] there is no read-modify-write.  Bits beyond
+    /// [`num_lines`] are ignored, and an empty mask does nothing.
+    ///
+    /// # Errors
+    ///
+    /// This requires lines requested through the v2 uAPI; for lines
+    /// requested through the v1 uAPI (on kernels before 5.10) an
+    /// [`ErrorKind::RequiresV2`] error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, LineRequestFlags};
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let bus = chip
+    ///     .get_lines(&[10, 11, 12, 13])?
+    ///     .request(LineRequestFlags::OUTPUT, &[0; 4], "bitbang")?;
+    /// // Raise the second and lower the third line, without touching the others
+    /// bus.set_values_masked(0b0110, 0b0010)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`set_values_partial`]: struct.MultiLineHandle.html#method.set_values_partial
+    /// [`num_lines`]: struct.MultiLineHandle.html#method.num_lines
+    /// [`ErrorKind::RequiresV2`]: errors/enum.ErrorKind.html#variant.RequiresV2
+    pub fn set_values_masked(&self, mask: u64, bits: u64) -> Result<()> {
+        if self.abi != Abi::V2 {
+            return Err(requires_v2_err());
+        }
+        let mask = mask & line_mask(self.num_lines());
+        if mask == 0 {
+            return Ok(());
+        }
+        let mut data = ffi::gpio_v2_line_values {
+            bits: bits & mask,
+            mask,
+        };
+        ffi::gpio_v2_line_set_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+        Ok(())
+    }
+
+    /// Change the values of some of the lines, leaving the others as they are
+    ///
+    /// Each update is a pair of the index of the line within this handle
+    /// (in the order the lines were requested) and its new value.  For
+    /// lines requested through the v2 uAPI the updates are applied
+    /// atomically with [`set_values_masked`].  Otherwise the current values
+    /// are read back from the kernel, the updates are applied and all
+    /// values are written again with a single ioctl.
+    ///
+    /// Note that in the latter case the read and the write are separate
+    /// calls, so a change made by another handle in between is overwritten.
+    ///
+    /// # Errors
+    ///
+    /// An index which is not less than [`num_lines`] results in an
+    /// [`ErrorKind::Index`] error without changing any line.
+    ///
+    /// [`num_lines`]: struct.MultiLineHandle.html#method.num_lines
+    /// [`set_values_masked`]: struct.MultiLineHandle.html#method.set_values_masked
+    /// [`ErrorKind::Index`]: errors/enum.ErrorKind.html#variant.Index
+    pub fn set_values_partial(&self, updates: &[(usize, u8)]) -> Result<()> {
+        let n = self.num_lines();
+        if let Some(&(index, _)) = updates.iter().find(|&&(index, _)| index >= n) {
+            return Err(index_err(index, n));
+        }
+        for &(_, value) in updates {
+            validate_value(value)?;
+        }
+        if self.abi == Abi::V2 {
+            let (mask, bits) = updates
+                .iter()
+                .fold((0, 0), |(mask, bits), &(index, value)| {
+                    let bit = 1 << index;
+                    let bits = if value != 0 { bits | bit } else { bits & !bit };
+                    (mask | bit, bits)
+                });
+            return self.set_values_masked(mask, bits);
+        }
+        let mut values = self.get_values()?;
+        for &(index, value) in updates {
+            values[index] = value;
+        }
+        self.set_values(&values)
+    }
+
+    /// Get the number of lines associated with this handle
+    pub fn num_lines(&self) -> usize {
+        self.lines.len()
     }
 
     /// Get the Line information associated with this handle.
@@ -879,163 +3850,1482 @@ impl MultiLineHandle {
     }
 }
 
-impl AsRawFd for MultiLineHandle {
-    /// Gets the raw file descriptor for the `LineHandle`.
-    fn as_raw_fd(&self) -> RawFd {
-        self.file.as_raw_fd()
+impl AsRawFd for MultiLineHandle {
+    /// Gets the raw file descriptor for the `LineHandle`.
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl AsFd for MultiLineHandle {
+    /// Gets the file descriptor for the `MultiLineHandle`.
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+/// Iterator over the sampled values of several lines
+///
+/// Created by [`MultiLineHandle::sample`].
+///
+/// [`MultiLineHandle::sample`]: struct.MultiLineHandle.html#method.sample
+#[derive(Debug)]
+pub struct MultiSamples<'a> {
+    handle: &'a MultiLineHandle,
+    timer: SampleTimer,
+}
+
+impl Iterator for MultiSamples<'_> {
+    type Item = Result<(Instant, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Result<(Instant, Vec<u8>)>> {
+        self.timer.wait();
+        let time = Instant::now();
+        Some(self.handle.get_values().map(|values| (time, values)))
+    }
+}
+
+/// Order in which the bits of a byte map to the lines of a [`ParallelPort`]
+///
+/// [`ParallelPort`]: struct.ParallelPort.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BitOrder {
+    /// The first line carries the least significant bit
+    #[default]
+    LsbFirst,
+    /// The first line carries the most significant bit
+    MsbFirst,
+}
+
+/// A group of up to 8 lines read and written as a byte
+///
+/// Wraps a [`MultiLineHandle`], e.g. for the data bus of a parallel
+/// (8080-style) display.  Bits map to the lines in the order they were
+/// requested, starting with the least significant bit unless configured
+/// otherwise with [`with_bit_order`].  A port of fewer than 8 lines (such
+/// as the 4-bit bus of a character LCD) uses the low bits of each byte.
+///
+/// With lines requested through the v2 uAPI all lines change at once;
+/// through the v1 uAPI the kernel sets them one after the other.
+///
+/// ```no_run
+/// # fn main() -> Result<(), gpio_cdev::Error> {
+/// use gpio_cdev::{BitOrder, Chip, LineRequestFlags, ParallelPort};
+///
+/// let mut chip = Chip::new("/dev/gpiochip0")?;
+/// let handle = chip
+///     .get_lines(&[8, 9, 10, 11, 12, 13, 14, 15])?
+///     .request(LineRequestFlags::OUTPUT, &[0; 8], "lcd-data")?;
+/// let port = ParallelPort::new(handle)?.with_bit_order(BitOrder::MsbFirst);
+/// port.write_byte(0x38)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MultiLineHandle`]: struct.MultiLineHandle.html
+/// [`with_bit_order`]: struct.ParallelPort.html#method.with_bit_order
+#[derive(Debug)]
+pub struct ParallelPort {
+    handle: MultiLineHandle,
+    bit_order: BitOrder,
+}
+
+impl ParallelPort {
+    /// Use the lines of `handle` as a port, least significant bit first
+    ///
+    /// # Errors
+    ///
+    /// A handle of no lines or of more than 8 lines results in an
+    /// [`ErrorKind::PortWidth`] error.
+    ///
+    /// [`ErrorKind::PortWidth`]: errors/enum.ErrorKind.html#variant.PortWidth
+    pub fn new(handle: MultiLineHandle) -> Result<Self> {
+        let n = handle.num_lines();
+        if n == 0 || n > 8 {
+            return Err(port_width_err(n));
+        }
+        Ok(Self {
+            handle,
+            bit_order: BitOrder::default(),
+        })
+    }
+
+    /// Map the bits to the lines in the given order
+    pub fn with_bit_order(mut self, bit_order: BitOrder) -> Self {
+        self.bit_order = bit_order;
+        self
+    }
+
+    /// The order in which the bits map to the lines
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Drive the lines to the bits of `byte`
+    ///
+    /// Bits beyond the width of the port are ignored.
+    pub fn write_byte(&self, byte: u8) -> Result<()> {
+        let bits = map_port_bits(byte, self.bit_order, self.handle.num_lines());
+        self.handle.set_values_bitmap(u64::from(bits))
+    }
+
+    /// Read the lines as a byte
+    ///
+    /// Bits beyond the width of the port are zero.
+    pub fn read_byte(&self) -> Result<u8> {
+        let bits = self.handle.get_values_bitmap()? as u8;
+        Ok(map_port_bits(bits, self.bit_order, self.handle.num_lines()))
+    }
+
+    /// The handle of the lines of the port
+    pub fn handle(&self) -> &MultiLineHandle {
+        &self.handle
+    }
+
+    /// Give up the port, returning the handle of its lines
+    pub fn into_inner(self) -> MultiLineHandle {
+        self.handle
+    }
+}
+
+/// Map between the bits of a byte and the bits of the `width` lines of a port
+///
+/// The mapping is its own inverse, so it serves both directions.
+fn map_port_bits(bits: u8, bit_order: BitOrder, width: usize) -> u8 {
+    match bit_order {
+        BitOrder::LsbFirst => bits,
+        // Line 0 carries the top bit of the port, not of the byte
+        BitOrder::MsbFirst => bits.reverse_bits() >> (8 - width),
+    }
+}
+
+/// Did the Line rise (go active) or fall (go inactive)?
+///
+/// Maps to kernel [`GPIOEVENT_EVENT_*`] definitions.
+///
+/// [`GPIOEVENT_EVENT_*`]: https://elixir.bootlin.com/linux/v4.9.127/source/include/uapi/linux/gpio.h#L136
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventType {
+    RisingEdge,
+    FallingEdge,
+}
+
+impl EventType {
+    /// Map the event id reported by the kernel, if it is a known one
+    ///
+    /// The v1 and v2 uAPIs use the same ids for rising and falling edges.
+    fn from_id(id: u32) -> Option<EventType> {
+        match id {
+            ffi::GPIOEVENT_EVENT_RISING_EDGE => Some(EventType::RisingEdge),
+            ffi::GPIOEVENT_EVENT_FALLING_EDGE => Some(EventType::FallingEdge),
+            _ => None,
+        }
+    }
+}
+
+/// An event as read from the kernel, before its id has been checked
+pub(crate) struct RawEvent {
+    timestamp: u64,
+    id: u32,
+    seqno: Option<u32>,
+    line_seqno: Option<u32>,
+    offset: Option<u32>,
+}
+
+impl RawEvent {
+    /// Reject events with an id we do not know how to interpret
+    pub(crate) fn checked(self) -> Result<LineEvent> {
+        match EventType::from_id(self.id) {
+            Some(event_type) => Ok(LineEvent {
+                timestamp: self.timestamp,
+                event_type,
+                id: self.id,
+                seqno: self.seqno,
+                line_seqno: self.line_seqno,
+                offset: self.offset,
+            }),
+            None => Err(unknown_event_err(self.id)),
+        }
+    }
+}
+
+/// Information about a change to the state of a Line
+///
+/// Wraps kernel [`struct gpioevent_data`] or, for events requested through
+/// the v2 uAPI, [`struct gpio_v2_line_event`].
+///
+/// [`struct gpioevent_data`]: https://elixir.bootlin.com/linux/v4.9.127/source/include/uapi/linux/gpio.h#L142
+/// [`struct gpio_v2_line_event`]: https://elixir.bootlin.com/linux/v5.10/source/include/uapi/linux/gpio.h#L269
+pub struct LineEvent {
+    timestamp: u64,
+    event_type: EventType,
+    id: u32,
+    seqno: Option<u32>,
+    line_seqno: Option<u32>,
+    offset: Option<u32>,
+}
+
+impl std::fmt::Debug for LineEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "LineEvent {{ timestamp: {:?}, event_type: {:?} }}",
+            self.timestamp(),
+            self.event_type()
+        )
+    }
+}
+
+impl LineEvent {
+    /// Best estimate of event occurrence time, in nanoseconds
+    ///
+    /// In most cases, the timestamp for the event is captured
+    /// in an interrupt handler so it should be very accurate.
+    ///
+    /// The nanosecond timestamp value should are captured
+    /// using the `CLOCK_MONOTONIC` offsets in the kernel and
+    /// should be compared against `CLOCK_MONOTONIC` values.
+    /// Note that kernel versions prior to 5.7 used
+    /// `CLOCK_REALTIME` offsets instead.
+    ///
+    /// For events requested with [`Line::events_with`] the clock is the
+    /// one selected with [`EventConfig::clock`].
+    ///
+    /// [`Line::events_with`]: struct.Line.html#method.events_with
+    /// [`EventConfig::clock`]: struct.EventConfig.html#method.clock
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Was this a rising or a falling edge?
+    pub fn event_type(&self) -> EventType {
+        self.event_type
+    }
+
+    /// The time elapsed between `earlier` and this event
+    ///
+    /// This is the difference of the [`timestamp`]s, e.g. the width of a
+    /// pulse given the events of its two edges.  If `earlier` actually
+    /// occurred after this event the result is zero, like
+    /// `Instant::saturating_duration_since`.  Both events must have been
+    /// timestamped with the same clock.
+    ///
+    /// [`timestamp`]: struct.LineEvent.html#method.timestamp
+    pub fn duration_since(&self, earlier: &LineEvent) -> Duration {
+        Duration::from_nanos(self.timestamp.saturating_sub(earlier.timestamp))
+    }
+
+    /// The event id as reported by the kernel
+    ///
+    /// [`event_type`] interprets this id; the raw value can help diagnosing
+    /// drivers behaving unexpectedly.
+    ///
+    /// [`event_type`]: struct.LineEvent.html#method.event_type
+    pub fn raw_id(&self) -> u32 {
+        self.id
+    }
+
+    /// Sequence number of this event among all events of the request
+    ///
+    /// Only available for events requested through the v2 uAPI (see
+    /// [`Line::events_with`]); `None` otherwise.  A gap between the
+    /// sequence numbers of consecutive events means that events were
+    /// dropped because the kernel event queue overflowed.
+    ///
+    /// [`Line::events_with`]: struct.Line.html#method.events_with
+    pub fn seqno(&self) -> Option<u32> {
+        self.seqno
+    }
+
+    /// Sequence number of this event among the events of this line
+    ///
+    /// Only available for events requested through the v2 uAPI (see
+    /// [`Line::events_with`]); `None` otherwise.
+    ///
+    /// [`Line::events_with`]: struct.Line.html#method.events_with
+    pub fn line_seqno(&self) -> Option<u32> {
+        self.line_seqno
+    }
+
+    /// Offset of the line which generated this event
+    ///
+    /// This is mostly useful for events read from a
+    /// [`MultiLineEventHandle`], which delivers the events of several
+    /// lines.  Events read from the kernel always carry their offset;
+    /// only simulated events (see the `mock` module) have none.
+    ///
+    /// [`MultiLineEventHandle`]: struct.MultiLineEventHandle.html
+    pub fn offset(&self) -> Option<u32> {
+        self.offset
+    }
+
+    /// Convert the event into a plain [`EdgeRecord`] for the given line
+    ///
+    /// Pass the offset of the line the event was read for, e.g.
+    /// `handle.line().offset()` or the value of [`offset`].
+    ///
+    /// [`EdgeRecord`]: struct.EdgeRecord.html
+    /// [`offset`]: struct.LineEvent.html#method.offset
+    pub fn into_record(&self, offset: u32) -> EdgeRecord {
+        EdgeRecord {
+            offset,
+            edge: self.event_type(),
+            timestamp_ns: self.timestamp,
+        }
+    }
+}
+
+/// Plain data describing an edge on a line
+///
+/// Created with [`LineEvent::into_record`].  Unlike [`LineEvent`], all
+/// fields are public, which makes records easy to log, send over a channel
+/// or store.  With the `serde` feature enabled, records (and
+/// [`EventType`]) implement `Serialize` and `Deserialize`.
+///
+/// [`LineEvent::into_record`]: struct.LineEvent.html#method.into_record
+/// [`LineEvent`]: struct.LineEvent.html
+/// [`EventType`]: enum.EventType.html
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeRecord {
+    /// Offset of the line within its chip
+    pub offset: u32,
+    /// Whether the line rose or fell
+    pub edge: EventType,
+    /// Timestamp of the event in nanoseconds, see [`LineEvent::timestamp`]
+    ///
+    /// [`LineEvent::timestamp`]: struct.LineEvent.html#method.timestamp
+    pub timestamp_ns: u64,
+}
+
+/// The version of the character device ABI a handle was requested with
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Abi {
+    V1,
+    V2,
+}
+
+/// Handle for retrieving events from the kernel for a line
+///
+/// In order for userspace to retrieve incoming events on a GPIO,
+/// an event handle must be requested from the chip using
+/// [`Line::events`].
+/// On success, the kernel creates an anonymous file descriptor
+/// for reading events. This structure is the go-between for callers
+/// and that file descriptor.
+///
+/// The same file descriptor also allows reading the current value of the
+/// line, so there is no need to request a separate [`LineHandle`] to both
+/// read the level and be notified of changes: use [`get_value`] next to
+/// the events, or [`events_with_level`] to get both at once.
+///
+/// As an iterator, the handle blocks until the next event is queued.
+/// Errors, such as `EAGAIN` in [non-blocking] mode, are yielded as items
+/// and do not end the iteration.  `None` is only returned after a short
+/// read, which the kernel does not produce for event requests; the
+/// iterator is not fused though, so reading may resume after a `None`.
+///
+/// ```no_run
+/// # fn main() -> Result<(), gpio_cdev::Error> {
+/// use gpio_cdev::{Chip, EventRequestFlags, LineRequestFlags};
+///
+/// let mut chip = Chip::new("/dev/gpiochip0")?;
+/// let mut handle = chip.get_line(4)?.events(
+///     LineRequestFlags::INPUT,
+///     EventRequestFlags::BOTH_EDGES,
+///     "door-sensor",
+/// )?;
+///
+/// println!("door is {}", if handle.get_value()? == 1 { "open" } else { "closed" });
+/// for event in &mut handle {
+///     println!("{:?}", event?.event_type());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Line::events`]: struct.Line.html#method.events
+/// [`LineHandle`]: struct.LineHandle.html
+/// [`get_value`]: struct.LineEventHandle.html#method.get_value
+/// [`events_with_level`]: struct.LineEventHandle.html#method.events_with_level
+/// [non-blocking]: struct.LineEventHandle.html#method.set_nonblocking
+#[derive(Debug)]
+pub struct LineEventHandle {
+    line: Line,
+    file: File,
+    abi: Abi,
+    /// The value of the line after the last event read, see `last_value()`
+    last_value: Option<u8>,
+    /// Whether to check the level after each edge, see `set_level_check()`
+    level_check: bool,
+    /// The level check suspects an event was lost and will report it next
+    loss_suspected: bool,
+}
+
+impl LineEventHandle {
+    /// Wrap a freshly requested event `file`, reading the initial value
+    fn new(line: Line, file: File, abi: Abi) -> Self {
+        let mut handle = LineEventHandle {
+            line,
+            file,
+            abi,
+            last_value: None,
+            level_check: false,
+            loss_suspected: false,
+        };
+        handle.last_value = handle.get_value().ok();
+        handle
+    }
+
+    /// The value of the line as of the last event read from this handle
+    ///
+    /// This is the value read when the handle was requested, updated with
+    /// every event read since: 1 after a rising edge and 0 after a falling
+    /// edge.  Unlike [`get_value`] this does not ask the kernel, so it is
+    /// only as current as the events which have been read; changes whose
+    /// events are still queued (or were lost) are not reflected.  Returns
+    /// `None` if the initial value could not be read.
+    ///
+    /// [`get_value`]: struct.LineEventHandle.html#method.get_value
+    pub fn last_value(&self) -> Option<u8> {
+        self.last_value
+    }
+
+    /// Retrieve the next event from the kernel for this line
+    ///
+    /// This blocks while there is not another event available from the
+    /// kernel for the line which matches the subscription criteria
+    /// specified in the `event_flags` when the handle was created.
+    ///
+    /// An [`ErrorKind::UnknownEvent`] error is returned if the kernel
+    /// reports an event which is neither a rising nor a falling edge.
+    ///
+    /// [`ErrorKind::UnknownEvent`]: errors/enum.ErrorKind.html#variant.UnknownEvent
+    pub fn get_event(&mut self) -> Result<LineEvent> {
+        if mem::take(&mut self.loss_suspected) {
+            return Err(events_lost_err(1));
+        }
+        match self.read_event() {
+            Ok(Some(event)) => self.check_level(event.checked()),
+            Ok(None) => Err(event_err(nix::errno::Errno::EIO)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Check the level of the line after each edge to detect lost events
+    ///
+    /// The v1 uAPI gives no indication when the kernel event queue
+    /// overflows and events are dropped, but a dropped edge usually shows
+    /// as a level which does not match the last event read.  With the
+    /// check enabled the value of the line is read after each edge, and if
+    /// it disagrees with the direction of the edge while no further event
+    /// is queued, the next call to [`get_event`] (or the iterator) returns
+    /// an [`ErrorKind::EventsLost`] error for at least one lost event
+    /// before carrying on with the following events.
+    ///
+    /// The check costs two syscalls per event and is a heuristic: a change
+    /// too short to be seen by the read, or an even number of lost edges,
+    /// goes unnoticed.  It only makes sense for handles requested with
+    /// [`EventRequestFlags::BOTH_EDGES`], as otherwise the edges which were
+    /// not requested would be reported as lost.  Handles requested through
+    /// the v2 uAPI can detect lost events reliably from
+    /// [`LineEvent::seqno`] instead.
+    ///
+    /// [`get_event`]: struct.LineEventHandle.html#method.get_event
+    /// [`ErrorKind::EventsLost`]: errors/enum.ErrorKind.html#variant.EventsLost
+    /// [`EventRequestFlags::BOTH_EDGES`]: struct.EventRequestFlags.html#associatedconstant.BOTH_EDGES
+    /// [`LineEvent::seqno`]: struct.LineEvent.html#method.seqno
+    pub fn set_level_check(&mut self, enabled: bool) {
+        self.level_check = enabled;
+        self.loss_suspected = false;
+    }
+
+    /// Apply the level check enabled with `set_level_check()` to `event`
+    fn check_level(&mut self, event: Result<LineEvent>) -> Result<LineEvent> {
+        if let (true, Ok(event)) = (self.level_check, &event) {
+            let expected = match event.event_type() {
+                EventType::RisingEdge => 1,
+                EventType::FallingEdge => 0,
+            };
+            // A level changed again since the edge is fine if its event is
+            // queued; only a change without an event means one was lost
+            if matches!(self.get_value(), Ok(value) if value != expected)
+                && !poll_readable(self.file.as_raw_fd(), 0).unwrap_or(true)
+            {
+                self.loss_suspected = true;
+            }
+        }
+        event
+    }
+
+    /// Request the current state of this Line from the kernel
+    ///
+    /// This value should be 0 or 1 which a "1" representing that
+    /// the line is active.  Usually this means that the line is
+    /// at logic-level high but it could mean the opposite if the
+    /// line has been marked as being `ACTIVE_LOW`.
+    pub fn get_value(&self) -> Result<u8> {
+        match self.abi {
+            Abi::V1 => {
+                let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
+                ffi::gpiohandle_get_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+                Ok(data.values[0])
+            }
+            Abi::V2 => {
+                let mut data = ffi::gpio_v2_line_values { bits: 0, mask: 1 };
+                ffi::gpio_v2_line_get_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+                Ok((data.bits & 1) as u8)
+            }
+        }
+    }
+
+    /// Put the handle into (or out of) non-blocking mode
+    ///
+    /// In non-blocking mode, reading an event when none is queued fails
+    /// with `EAGAIN` instead of waiting for one, as needed for building
+    /// event loops on the raw file descriptor (e.g. with epoll).
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        set_nonblocking(&self.file, nonblocking)
+    }
+
+    /// Number of complete events queued by the kernel, using `FIONREAD`
+    ///
+    /// This allows sizing a buffer before reading a batch of events.
+    ///
+    /// # Errors
+    ///
+    /// Whether `FIONREAD` works depends on the kernel: the GPIO character
+    /// device does not implement it as of Linux 6.x, in which case the
+    /// kernel's error (`EINVAL` or `ENOTTY`) is returned as an
+    /// [`ErrorKind::Io`] error.  Use [`drain`] or `poll(2)` to deal with
+    /// queued events on such kernels.
+    ///
+    /// [`ErrorKind::Io`]: errors/enum.ErrorKind.html#variant.Io
+    /// [`drain`]: struct.LineEventHandle.html#method.drain
+    pub fn pending_events(&self) -> Result<usize> {
+        let mut bytes: libc::c_int = 0;
+        if unsafe { libc::ioctl(self.file.as_raw_fd(), libc::FIONREAD, &mut bytes) } == -1 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let event_size = match self.abi {
+            Abi::V1 => mem::size_of::<ffi::gpioevent_data>(),
+            Abi::V2 => mem::size_of::<ffi::gpio_v2_line_event>(),
+        };
+        Ok(bytes.max(0) as usize / event_size)
+    }
+
+    /// Discard all events currently queued by the kernel for this line
+    ///
+    /// This does not block; events which arrive after the queue has been
+    /// emptied are kept.  Returns the number of events discarded.
+    ///
+    /// This is useful to start afresh from the current level of the line,
+    /// e.g. after reconfiguring it or after not reading events for a while.
+    pub fn drain(&mut self) -> Result<usize> {
+        let mut dropped = 0;
+        while poll_readable(self.file.as_raw_fd(), 0)? {
+            match self.read_event()? {
+                Some(_) => dropped += 1,
+                None => break,
+            }
+        }
+        Ok(dropped)
+    }
+
+    /// Collect the events occurring within `window`
+    ///
+    /// Events are read until `window` has elapsed from the time of the
+    /// call and returned in the order they occurred, e.g. to measure a
+    /// pulse train.  Events already queued when this is called are included,
+    /// even with a `window` of zero.
+    ///
+    /// An [`ErrorKind::UnknownEvent`] error is returned if the kernel
+    /// reports an event which is neither a rising nor a falling edge.
+    ///
+    /// [`ErrorKind::UnknownEvent`]: errors/enum.ErrorKind.html#variant.UnknownEvent
+    pub fn collect_for(&mut self, window: Duration) -> Result<Vec<LineEvent>> {
+        let deadline = Instant::now() + window;
+        let mut events = Vec::new();
+        // Take the events already queued even if the window is empty
+        while poll_readable(self.file.as_raw_fd(), 0)? {
+            events.push(self.get_event()?);
+        }
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            // Stop at the deadline even if events keep arriving
+            if remaining == Duration::ZERO
+                || !poll_readable(self.file.as_raw_fd(), timeout_ms(Some(remaining)))?
+            {
+                return Ok(events);
+            }
+            events.push(self.get_event()?);
+        }
+    }
+
+    /// Count the events currently queued by the kernel for this line
+    ///
+    /// Like [`drain`], this reads all queued events without blocking and
+    /// discards them, but returns the number of rising and falling edges
+    /// among them as `(rising, falling)`.  This is handy when only the
+    /// number of edges matters, e.g. for a rotary encoder polled once per
+    /// control cycle.
+    ///
+    /// An [`ErrorKind::UnknownEvent`] error is returned if the kernel
+    /// reports an event which is neither a rising nor a falling edge.
+    ///
+    /// [`drain`]: struct.LineEventHandle.html#method.drain
+    /// [`ErrorKind::UnknownEvent`]: errors/enum.ErrorKind.html#variant.UnknownEvent
+    pub fn count_edges(&mut self) -> Result<(usize, usize)> {
+        let (mut rising, mut falling) = (0, 0);
+        while poll_readable(self.file.as_raw_fd(), 0)? {
+            match self.read_event()? {
+                Some(event) => match event.checked()?.event_type() {
+                    EventType::RisingEdge => rising += 1,
+                    EventType::FallingEdge => falling += 1,
+                },
+                None => break,
+            }
+        }
+        Ok((rising, falling))
+    }
+
+    /// Iterate over events, ignoring edges which follow the previous edge too closely
+    ///
+    /// This is a software debounce: an event is only returned if it occurred
+    /// at least `window` after the previously returned event, according to
+    /// the kernel timestamps.  Suppressed events are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), gpio_cdev::Error> {
+    /// use gpio_cdev::{Chip, EventRequestFlags, LineRequestFlags};
+    /// use std::time::Duration;
+    ///
+    /// let mut chip = Chip::new("/dev/gpiochip0")?;
+    /// let mut handle = chip.get_line(4)?.events(
+    ///     LineRequestFlags::INPUT,
+    ///     EventRequestFlags::BOTH_EDGES,
+    ///     "debounced-button",
+    /// )?;
+    /// for event in handle.debounced(Duration::from_millis(20)) {
+    ///     println!("{:?}", event?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn debounced(&mut self, window: Duration) -> DebouncedEvents<'_> {
+        DebouncedEvents {
+            handle: self,
+            window: window.as_nanos().min(u128::from(u64::MAX)) as u64,
+            last: None,
+        }
+    }
+
+    /// Iterate over events together with the level of the line after each
+    ///
+    /// The level is inferred from the edge (1 after a rising edge, 0 after
+    /// a falling edge) rather than read from the kernel, which saves an
+    /// ioctl per event.  Like [`get_value`] it is the logical level, taking
+    /// `ACTIVE_LOW` into account.  This is most useful for lines requested
+    /// with [`EventRequestFlags::BOTH_EDGES`]; if events were lost the
+    /// inferred level may briefly be out of date.
+    ///
+    /// [`get_value`]: struct.LineEventHandle.html#method.get_value
+    /// [`EventRequestFlags::BOTH_EDGES`]: struct.EventRequestFlags.html#associatedconstant.BOTH_EDGES
+    pub fn events_with_level(&mut self) -> EventsWithLevel<'_> {
+        EventsWithLevel { handle: self }
+    }
+
+    /// Change the configuration of the line in place
+    ///
+    /// This allows e.g. switching between rising and both edges without
+    /// releasing the line.  Events already queued are kept; use [`drain`]
+    /// to discard them.
+    ///
+    /// # Errors
+    ///
+    /// Only handles requested through the v2 uAPI with
+    /// [`Line::events_with`] can be reconfigured; for other handles an
+    /// [`ErrorKind::RequiresV2`] error is returned.
+    ///
+    /// [`drain`]: struct.LineEventHandle.html#method.drain
+    /// [`Line::events_with`]: struct.Line.html#method.events_with
+    /// [`ErrorKind::RequiresV2`]: errors/enum.ErrorKind.html#variant.RequiresV2
+    pub fn set_config(&mut self, config: EventConfig) -> Result<()> {
+        if self.abi != Abi::V2 {
+            return Err(requires_v2_err());
+        }
+        config.handle_flags.validate_events()?;
+        config.event_flags.validate()?;
+        let mut v2_config = config.v2_config(1);
+        ffi::gpio_v2_line_set_config_ioctl(self.file.as_raw_fd(), &mut v2_config)?;
+        Ok(())
+    }
+
+    /// Create a second handle sharing the same kernel file descriptor
+    ///
+    /// The new handle refers to the same line request (via `dup(2)`), so it
+    /// can e.g. be moved to another thread to read the value of the line.
+    ///
+    /// Note that both handles read from the *same* kernel event queue: each
+    /// event is delivered to only one of them.  Only read events from one of
+    /// the handles unless that distribution is what you want.
+    pub fn try_clone(&self) -> Result<LineEventHandle> {
+        Ok(LineEventHandle {
+            line: self.line.clone(),
+            file: self.file.try_clone()?,
+            abi: self.abi,
+            last_value: self.last_value,
+            level_check: self.level_check,
+            loss_suspected: false,
+        })
+    }
+
+    /// Get the Line information associated with this handle.
+    pub fn line(&self) -> &Line {
+        &self.line
+    }
+
+    /// Offset of the line this handle delivers events for
+    pub fn event_offset(&self) -> u32 {
+        self.line.offset()
+    }
+
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    pub fn file2(&mut self) -> &File {
+        &self.file
+    }
+
+    /// Helper function which returns the line event if a complete event was read, Ok(None) if not
+    /// enough data was read or the error returned by `read()`.
+    pub(crate) fn read_event(&mut self) -> std::io::Result<Option<RawEvent>> {
+        let event = match self.abi {
+            Abi::V1 => {
+                let data: Option<ffi::gpioevent_data> = read_struct(&self.file)?;
+                data.map(|data| RawEvent {
+                    timestamp: data.timestamp,
+                    id: data.id,
+                    seqno: None,
+                    line_seqno: None,
+                    offset: Some(self.line.offset()),
+                })
+            }
+            Abi::V2 => {
+                let data: Option<ffi::gpio_v2_line_event> = read_struct(&self.file)?;
+                data.map(|data| RawEvent {
+                    timestamp: data.timestamp_ns,
+                    id: data.id,
+                    seqno: Some(data.seqno),
+                    line_seqno: Some(data.line_seqno),
+                    offset: Some(data.offset),
+                })
+            }
+        };
+        if let Some(event_type) = event.as_ref().and_then(|e| EventType::from_id(e.id)) {
+            self.last_value = Some(match event_type {
+                EventType::RisingEdge => 1,
+                EventType::FallingEdge => 0,
+            });
+        }
+        Ok(event)
+    }
+}
+
+/// Iterator over the debounced events of a line
+///
+/// Created by [`LineEventHandle::debounced`].
+///
+/// [`LineEventHandle::debounced`]: struct.LineEventHandle.html#method.debounced
+#[derive(Debug)]
+pub struct DebouncedEvents<'a> {
+    handle: &'a mut LineEventHandle,
+    window: u64,
+    last: Option<u64>,
+}
+
+impl DebouncedEvents<'_> {
+    /// Request the current state of the line from the kernel
+    ///
+    /// See [`LineEventHandle::get_value`].
+    ///
+    /// [`LineEventHandle::get_value`]: struct.LineEventHandle.html#method.get_value
+    pub fn get_value(&self) -> Result<u8> {
+        self.handle.get_value()
+    }
+}
+
+impl Iterator for DebouncedEvents<'_> {
+    type Item = Result<LineEvent>;
+
+    fn next(&mut self) -> Option<Result<LineEvent>> {
+        loop {
+            let event = match self.handle.next()? {
+                Ok(event) => event,
+                Err(e) => return Some(Err(e)),
+            };
+            if let Some(last) = self.last {
+                if event.timestamp().wrapping_sub(last) < self.window {
+                    continue;
+                }
+            }
+            self.last = Some(event.timestamp());
+            return Some(Ok(event));
+        }
     }
 }
 
-/// Did the Line rise (go active) or fall (go inactive)?
+/// Iterator over the events of a line and the level following each
 ///
-/// Maps to kernel [`GPIOEVENT_EVENT_*`] definitions.
+/// Created by [`LineEventHandle::events_with_level`].
 ///
-/// [`GPIOEVENT_EVENT_*`]: https://elixir.bootlin.com/linux/v4.9.127/source/include/uapi/linux/gpio.h#L136
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum EventType {
-    RisingEdge,
-    FallingEdge,
+/// [`LineEventHandle::events_with_level`]: struct.LineEventHandle.html#method.events_with_level
+#[derive(Debug)]
+pub struct EventsWithLevel<'a> {
+    handle: &'a mut LineEventHandle,
 }
 
-/// Information about a change to the state of a Line
+impl Iterator for EventsWithLevel<'_> {
+    type Item = Result<(LineEvent, u8)>;
+
+    fn next(&mut self) -> Option<Result<(LineEvent, u8)>> {
+        let event = match self.handle.next()? {
+            Ok(event) => event,
+            Err(e) => return Some(Err(e)),
+        };
+        let level = match event.event_type() {
+            EventType::RisingEdge => 1,
+            EventType::FallingEdge => 0,
+        };
+        Some(Ok((event, level)))
+    }
+}
+
+/// Blocking monitor for the events of several lines
 ///
-/// Wraps kernel [`struct gpioevent_data`].
+/// This waits on the events of any number of [`LineEventHandle`]s at once
+/// using `poll(2)`, so a single thread can service many lines without
+/// busy-waiting.  Each handle is paired with an identifier, usually the
+/// offset of its line, which is returned along with its events.
 ///
-/// [`struct gpioevent_data`]: https://elixir.bootlin.com/linux/v4.9.127/source/include/uapi/linux/gpio.h#L142
-pub struct LineEvent(ffi::gpioevent_data);
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), gpio_cdev::Error> {
+/// use gpio_cdev::{Chip, EventRequestFlags, LineRequestFlags, MultiLineMonitor};
+///
+/// let mut chip = Chip::new("/dev/gpiochip0")?;
+/// let mut handles = Vec::new();
+/// for &offset in &[4, 5, 6] {
+///     let handle = chip.get_line(offset)?.events(
+///         LineRequestFlags::INPUT,
+///         EventRequestFlags::BOTH_EDGES,
+///         "monitor",
+///     )?;
+///     handles.push((offset, handle));
+/// }
+/// let mut monitor = MultiLineMonitor::new(handles);
+///
+/// loop {
+///     for (offset, event) in monitor.wait(None)? {
+///         println!("[{}] {:?}", offset, event);
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`LineEventHandle`]: struct.LineEventHandle.html
+#[derive(Debug)]
+pub struct MultiLineMonitor {
+    handles: Vec<(u32, LineEventHandle)>,
+}
 
-impl std::fmt::Debug for LineEvent {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "LineEvent {{ timestamp: {:?}, event_type: {:?} }}",
-            self.timestamp(),
-            self.event_type()
-        )
+impl MultiLineMonitor {
+    /// Monitor the given handles, each paired with an identifier
+    pub fn new<I>(handles: I) -> MultiLineMonitor
+    where
+        I: IntoIterator<Item = (u32, LineEventHandle)>,
+    {
+        MultiLineMonitor {
+            handles: handles.into_iter().collect(),
+        }
     }
-}
 
-impl LineEvent {
-    /// Best estimate of event occurrence time, in nanoseconds
+    /// The monitored handles, in the order they were provided
+    pub fn handles(&self) -> &[(u32, LineEventHandle)] {
+        &self.handles
+    }
+
+    /// Wait until at least one of the lines has an event
     ///
-    /// In most cases, the timestamp for the event is captured
-    /// in an interrupt handler so it should be very accurate.
+    /// Returns one event, paired with the identifier of its handle, for
+    /// each line with a pending event, in the order the handles were
+    /// provided.  Returns an empty list if no event occurred within
+    /// `timeout`; with a `timeout` of `None` this waits forever.  A monitor
+    /// without handles returns immediately.
     ///
-    /// The nanosecond timestamp value should are captured
-    /// using the `CLOCK_MONOTONIC` offsets in the kernel and
-    /// should be compared against `CLOCK_MONOTONIC` values.
-    /// Note that kernel versions prior to 5.7 used
-    /// `CLOCK_REALTIME` offsets instead.
-    pub fn timestamp(&self) -> u64 {
-        self.0.timestamp
+    /// Further events already queued for a line are returned by the next
+    /// call, which then does not block.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<Vec<(u32, LineEvent)>> {
+        if self.handles.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pollfds: Vec<libc::pollfd> = self
+            .handles
+            .iter()
+            .map(|(_, handle)| libc::pollfd {
+                fd: handle.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            })
+            .collect();
+        if poll_fds(&mut pollfds, timeout_ms(timeout))? == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut events = Vec::new();
+        for (pollfd, (id, handle)) in pollfds.iter().zip(self.handles.iter_mut()) {
+            if pollfd.revents & libc::POLLIN != 0 {
+                events.push((*id, handle.get_event()?));
+            }
+        }
+        Ok(events)
     }
+}
 
-    /// Was this a rising or a falling edge?
-    pub fn event_type(&self) -> EventType {
-        if self.0.id == 0x01 {
-            EventType::RisingEdge
-        } else {
-            EventType::FallingEdge
+/// Convert an optional timeout to milliseconds for `poll(2)`, rounding up
+fn timeout_ms(timeout: Option<Duration>) -> libc::c_int {
+    match timeout {
+        None => -1,
+        Some(timeout) => {
+            let ms = timeout.as_millis() + u128::from(timeout.subsec_nanos() % 1_000_000 != 0);
+            libc::c_int::try_from(ms).unwrap_or(libc::c_int::MAX)
         }
     }
 }
 
-/// Handle for retrieving events from the kernel for a line
+/// Wait up to `timeout_ms` milliseconds (forever if negative) for `fd` to become readable.
+fn poll_readable(fd: RawFd, timeout_ms: libc::c_int) -> Result<bool> {
+    let mut pollfd = [libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    }];
+    match poll_fds(&mut pollfd, timeout_ms)? {
+        0 => Ok(false),
+        _ => Ok(pollfd[0].revents & libc::POLLIN != 0),
+    }
+}
+
+/// `poll(2)` the given file descriptors, retrying if interrupted by a signal
 ///
-/// In order for userspace to retrieve incoming events on a GPIO,
-/// an event handle must be requested from the chip using
-/// [`Line::events`].
-/// On success, the kernel creates an anonymous file descriptor
-/// for reading events. This structure is the go-between for callers
-/// and that file descriptor.
+/// Returns the number of file descriptors with events, 0 on timeout.
+fn poll_fds(pollfds: &mut [libc::pollfd], timeout_ms: libc::c_int) -> Result<usize> {
+    loop {
+        match unsafe {
+            libc::poll(
+                pollfds.as_mut_ptr(),
+                pollfds.len() as libc::nfds_t,
+                timeout_ms,
+            )
+        } {
+            -1 => {
+                let err = std::io::Error::last_os_error();
+                if err.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(err.into());
+                }
+            }
+            n => return Ok(n as usize),
+        }
+    }
+}
+
+/// Close `file`, reporting the error `Drop` would ignore
+fn close_file(file: File) -> Result<()> {
+    if unsafe { libc::close(file.into_raw_fd()) } == -1 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Read a single kernel structure from `file`, returning `Ok(None)` on a short read.
 ///
-/// [`Line::events`]: struct.Line.html#method.events
+/// Only to be used with the plain-data `ffi` structures, for which any bit
+/// pattern is valid.
+fn read_struct<T>(mut file: &File) -> std::io::Result<Option<T>> {
+    let mut data: T = unsafe { mem::zeroed() };
+    let data_as_buf =
+        unsafe { slice::from_raw_parts_mut((&mut data as *mut T).cast(), mem::size_of::<T>()) };
+    let bytes_read = file.read(data_as_buf)?;
+    if bytes_read == mem::size_of::<T>() {
+        Ok(Some(data))
+    } else {
+        Ok(None)
+    }
+}
+
+impl AsRawFd for LineEventHandle {
+    /// Gets the raw file descriptor for the `LineEventHandle`.
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl AsFd for LineEventHandle {
+    /// Gets the raw file descriptor for the `LineEventHandle`.
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+impl Iterator for LineEventHandle {
+    type Item = Result<LineEvent>;
+
+    fn next(&mut self) -> Option<Result<LineEvent>> {
+        if mem::take(&mut self.loss_suspected) {
+            return Some(Err(events_lost_err(1)));
+        }
+        match self.read_event() {
+            Ok(None) => None,
+            Ok(Some(event)) => Some(self.check_level(event.checked())),
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+/// Set or clear `O_NONBLOCK` on `file`
+fn set_nonblocking(file: &File, nonblocking: bool) -> Result<()> {
+    let fd = file.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL, 0) };
+    if flags == -1 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let flags = if nonblocking {
+        flags | libc::O_NONBLOCK
+    } else {
+        flags & !libc::O_NONBLOCK
+    };
+    if unsafe { libc::fcntl(fd, libc::F_SETFL, flags) } == -1 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Handle for retrieving events from the kernel for several lines
+///
+/// Created by [`Lines::events`].  All lines share a single file
+/// descriptor; each event carries the offset of the line which generated
+/// it (see [`LineEvent::offset`]).  As an iterator, it behaves like
+/// [`LineEventHandle`] and is not fused either.
+///
+/// [`Lines::events`]: struct.Lines.html#method.events
+/// [`LineEvent::offset`]: struct.LineEvent.html#method.offset
+/// [`LineEventHandle`]: struct.LineEventHandle.html
 #[derive(Debug)]
-pub struct LineEventHandle {
-    line: Line,
+pub struct MultiLineEventHandle {
+    lines: Lines,
     file: File,
 }
 
-impl LineEventHandle {
-    /// Retrieve the next event from the kernel for this line
+impl MultiLineEventHandle {
+    /// Retrieve the next event from the kernel for any of the lines
     ///
-    /// This blocks while there is not another event available from the
-    /// kernel for the line which matches the subscription criteria
-    /// specified in the `event_flags` when the handle was created.
+    /// This blocks while there is no event available.  An
+    /// [`ErrorKind::UnknownEvent`] error is returned if the kernel reports
+    /// an event which is neither a rising nor a falling edge.
+    ///
+    /// [`ErrorKind::UnknownEvent`]: errors/enum.ErrorKind.html#variant.UnknownEvent
     pub fn get_event(&mut self) -> Result<LineEvent> {
         match self.read_event() {
-            Ok(Some(event)) => Ok(event),
+            Ok(Some(event)) => event.checked(),
             Ok(None) => Err(event_err(nix::errno::Errno::EIO)),
             Err(e) => Err(e.into()),
         }
     }
 
-    /// Request the current state of this Line from the kernel
+    /// Request the current state of the lines from the kernel
     ///
-    /// This value should be 0 or 1 which a "1" representing that
-    /// the line is active.  Usually this means that the line is
-    /// at logic-level high but it could mean the opposite if the
-    /// line has been marked as being `ACTIVE_LOW`.
-    pub fn get_value(&self) -> Result<u8> {
-        let mut data: ffi::gpiohandle_data = unsafe { mem::zeroed() };
-        ffi::gpiohandle_get_line_values_ioctl(self.file.as_raw_fd(), &mut data)?;
-        Ok(data.values[0])
+    /// The values are returned in the order the lines were requested in.
+    pub fn get_values(&self) -> Result<Vec<u8>> {
+        let n = self.num_lines();
+        let mut data = ffi::gpio_v2_line_values {
+            bits: 0,
+            mask: line_mask(n),
+        };
+        ffi::gpio_v2_line_get_values_ioctl(self.file.as_raw_fd(), &mut data)?;
+        Ok((0..n).map(|i| ((data.bits >> i) & 1) as u8).collect())
     }
 
-    /// Get the Line information associated with this handle.
-    pub fn line(&self) -> &Line {
-        &self.line
+    /// Put the handle into (or out of) non-blocking mode
+    ///
+    /// See [`LineEventHandle::set_nonblocking`].
+    ///
+    /// [`LineEventHandle::set_nonblocking`]: struct.LineEventHandle.html#method.set_nonblocking
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        set_nonblocking(&self.file, nonblocking)
     }
 
-    pub fn file(&self) -> &File {
-        &self.file
+    /// Get the Line information associated with this handle.
+    pub fn lines(&self) -> &Lines {
+        &self.lines
     }
 
-    pub fn file2(&mut self) -> &File {
-        &self.file
+    /// Get the number of lines associated with this handle
+    pub fn num_lines(&self) -> usize {
+        self.lines.len()
     }
 
     /// Helper function which returns the line event if a complete event was read, Ok(None) if not
     /// enough data was read or the error returned by `read()`.
-    pub(crate) fn read_event(&mut self) -> std::io::Result<Option<LineEvent>> {
-        let mut data: ffi::gpioevent_data = unsafe { mem::zeroed() };
-        let data_as_buf = unsafe {
-            slice::from_raw_parts_mut(
-                (&mut data as *mut ffi::gpioevent_data).cast(),
-                mem::size_of::<ffi::gpioevent_data>(),
-            )
-        };
-        let bytes_read = self.file.read(data_as_buf)?;
-        if bytes_read == mem::size_of::<ffi::gpioevent_data>() {
-            Ok(Some(LineEvent(data)))
-        } else {
-            Ok(None)
-        }
+    pub(crate) fn read_event(&mut self) -> std::io::Result<Option<RawEvent>> {
+        let data: Option<ffi::gpio_v2_line_event> = read_struct(&self.file)?;
+        Ok(data.map(|data| RawEvent {
+            timestamp: data.timestamp_ns,
+            id: data.id,
+            seqno: Some(data.seqno),
+            line_seqno: Some(data.line_seqno),
+            offset: Some(data.offset),
+        }))
     }
 }
 
-impl AsRawFd for LineEventHandle {
-    /// Gets the raw file descriptor for the `LineEventHandle`.
+impl AsRawFd for MultiLineEventHandle {
+    /// Gets the raw file descriptor for the `MultiLineEventHandle`.
     fn as_raw_fd(&self) -> RawFd {
         self.file.as_raw_fd()
     }
 }
 
-impl AsFd for LineEventHandle {
-    /// Gets the raw file descriptor for the `LineEventHandle`.
+impl AsFd for MultiLineEventHandle {
+    /// Gets the raw file descriptor for the `MultiLineEventHandle`.
     fn as_fd(&self) -> BorrowedFd<'_> {
         self.file.as_fd()
     }
 }
 
-impl Iterator for LineEventHandle {
+impl Iterator for MultiLineEventHandle {
     type Item = Result<LineEvent>;
 
     fn next(&mut self) -> Option<Result<LineEvent>> {
         match self.read_event() {
             Ok(None) => None,
-            Ok(Some(event)) => Some(Ok(event)),
+            Ok(Some(event)) => Some(event.checked()),
             Err(e) => Some(Err(e.into())),
         }
     }
 }
+
+/// A line whose value can be read
+///
+/// Implemented by the handles of this crate as well as by the in-memory
+/// lines of the [`mock`] module (with the `mock` feature), so that code
+/// written against these traits can be tested without GPIO hardware.
+///
+/// [`mock`]: mock/index.html
+pub trait LineInput {
+    /// Read the current value of the line; see [`LineHandle::get_value`]
+    ///
+    /// [`LineHandle::get_value`]: struct.LineHandle.html#method.get_value
+    fn get_value(&self) -> Result<u8>;
+}
+
+/// A line which can be driven to a value
+///
+/// See [`LineInput`] for the purpose of these traits.
+///
+/// [`LineInput`]: trait.LineInput.html
+pub trait LineOutput: LineInput {
+    /// Drive the line to `value`; see [`LineHandle::set_value`]
+    ///
+    /// [`LineHandle::set_value`]: struct.LineHandle.html#method.set_value
+    fn set_value(&self, value: u8) -> Result<()>;
+}
+
+/// A source of events for a line
+///
+/// See [`LineInput`] for the purpose of these traits.
+///
+/// [`LineInput`]: trait.LineInput.html
+pub trait LineEvents: LineInput {
+    /// Retrieve the next event; see [`LineEventHandle::get_event`]
+    ///
+    /// [`LineEventHandle::get_event`]: struct.LineEventHandle.html#method.get_event
+    fn get_event(&mut self) -> Result<LineEvent>;
+}
+
+impl LineInput for LineHandle {
+    fn get_value(&self) -> Result<u8> {
+        LineHandle::get_value(self)
+    }
+}
+
+impl LineOutput for LineHandle {
+    fn set_value(&self, value: u8) -> Result<()> {
+        LineHandle::set_value(self, value)
+    }
+}
+
+impl LineInput for InputLineHandle {
+    fn get_value(&self) -> Result<u8> {
+        InputLineHandle::get_value(self)
+    }
+}
+
+impl LineInput for OutputLineHandle {
+    fn get_value(&self) -> Result<u8> {
+        OutputLineHandle::get_value(self)
+    }
+}
+
+impl LineOutput for OutputLineHandle {
+    fn set_value(&self, value: u8) -> Result<()> {
+        OutputLineHandle::set_value(self, value)
+    }
+}
+
+impl LineInput for LineEventHandle {
+    fn get_value(&self) -> Result<u8> {
+        LineEventHandle::get_value(self)
+    }
+}
+
+impl LineEvents for LineEventHandle {
+    fn get_event(&mut self) -> Result<LineEvent> {
+        LineEventHandle::get_event(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consumer_fits() {
+        assert_eq!(str_prefix("rust-gpio", CONSUMER_SIZE), b"rust-gpio");
+        assert_eq!(str_prefix("", CONSUMER_SIZE), b"");
+    }
+
+    #[test]
+    fn consumer_truncated_to_buffer() {
+        let label = "a".repeat(40);
+        assert_eq!(str_prefix(&label, CONSUMER_SIZE), &label.as_bytes()[..31]);
+    }
+
+    #[test]
+    fn consumer_truncated_at_char_boundary() {
+        // 30 ASCII bytes followed by a 3 byte character which does not fit
+        let label = format!("{}€", "a".repeat(30));
+        assert_eq!(str_prefix(&label, CONSUMER_SIZE), &label.as_bytes()[..30]);
+        // A character ending exactly at the limit is kept
+        let label = format!("{}€x", "a".repeat(28));
+        assert_eq!(str_prefix(&label, CONSUMER_SIZE), &label.as_bytes()[..31]);
+    }
+
+    #[test]
+    fn consumer_copied_with_nul() {
+        let mut buf = [0x55 as libc::c_char; 8];
+        unsafe { bytes_lcpy(buf.as_mut_ptr(), str_prefix("häß", buf.len()), buf.len()) };
+        let bytes: Vec<u8> = buf.iter().map(|&c| c as u8).collect();
+        assert_eq!(&bytes[..6], "häß\0".as_bytes());
+
+        let mut buf = [0x55 as libc::c_char; 4];
+        unsafe { bytes_lcpy(buf.as_mut_ptr(), str_prefix("häß", buf.len()), buf.len()) };
+        let bytes: Vec<u8> = buf.iter().map(|&c| c as u8).collect();
+        assert_eq!(&bytes[..4], "hä\0".as_bytes());
+    }
+
+    #[test]
+    fn consumer_with_nul_rejected() {
+        for label in ["a\0b", "\0", "trailing\0"] {
+            assert_eq!(
+                consumer_label(label).unwrap_err().kind(),
+                &ErrorKind::InvalidConsumer(label.as_bytes().to_vec())
+            );
+        }
+        // Also beyond the part which would be stored
+        let label = format!("{}\0", "a".repeat(40));
+        assert!(consumer_label(&label).is_err());
+        assert_eq!(consumer_label("rust-gpio").unwrap(), b"rust-gpio");
+        assert!(validate_consumer(&[0xff, 0xfe]).is_ok());
+        assert!(validate_consumer(&[0xff, 0]).is_err());
+    }
+
+    #[test]
+    fn line_spec_parses() {
+        let spec: LineSpec = "gpiochip0:17".parse().unwrap();
+        assert_eq!(spec, LineSpec::new("/dev/gpiochip0", 17));
+        let spec: LineSpec = "/dev/gpio/by-name/expander:0".parse().unwrap();
+        assert_eq!(spec.chip_path(), Path::new("/dev/gpio/by-name/expander"));
+        assert_eq!(spec.offset(), 0);
+        // Only the last colon separates the offset
+        let spec: LineSpec = "./a:b:4294967295".parse().unwrap();
+        assert_eq!(spec, LineSpec::new("./a:b", u32::MAX));
+    }
+
+    #[test]
+    fn line_spec_displays_parseably() {
+        let spec = LineSpec::new("/dev/gpiochip1", 3);
+        assert_eq!(spec.to_string(), "/dev/gpiochip1:3");
+        assert_eq!(spec.to_string().parse::<LineSpec>().unwrap(), spec);
+    }
+
+    #[test]
+    fn line_spec_rejects_invalid() {
+        for spec in [
+            "",
+            "gpiochip0",
+            ":17",
+            "gpiochip0:",
+            "gpiochip0:x",
+            "gpiochip0:-1",
+            "gpiochip0:4294967296",
+        ] {
+            assert_eq!(
+                spec.parse::<LineSpec>().unwrap_err().kind(),
+                &ErrorKind::InvalidLineSpec(spec.to_owned()),
+                "{:?}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn line_configs_group_flags() {
+        let out = LineRequestFlags::OUTPUT;
+        let out_low = LineRequestFlags::OUTPUT | LineRequestFlags::ACTIVE_LOW;
+        let flags = [out.clone(), out_low, out.clone(), LineRequestFlags::INPUT];
+        let config = v2_line_configs(&flags, &[1, 0, 1, 0]).unwrap();
+
+        assert_eq!(config.flags, out.v2_flags());
+        assert_eq!(config.num_attrs, 3);
+        let attrs = &config.attrs;
+        assert_eq!(attrs[0].attr.id, ffi::GPIO_V2_LINE_ATTR_ID_FLAGS);
+        assert_eq!(unsafe { attrs[0].attr.value.flags }, flags[1].v2_flags());
+        assert_eq!(attrs[0].mask, 0b0010);
+        assert_eq!(attrs[1].attr.id, ffi::GPIO_V2_LINE_ATTR_ID_FLAGS);
+        assert_eq!(
+            unsafe { attrs[1].attr.value.flags },
+            LineRequestFlags::INPUT.v2_flags()
+        );
+        assert_eq!(attrs[1].mask, 0b1000);
+        // Output values only cover the outputs
+        assert_eq!(attrs[2].attr.id, ffi::GPIO_V2_LINE_ATTR_ID_OUTPUT_VALUES);
+        assert_eq!(unsafe { attrs[2].attr.value.values }, 0b0101);
+        assert_eq!(attrs[2].mask, 0b0111);
+    }
+
+    #[test]
+    fn line_configs_without_outputs() {
+        let flags = [
+            LineRequestFlags::INPUT,
+            LineRequestFlags::INPUT | LineRequestFlags::ACTIVE_LOW,
+            LineRequestFlags::INPUT,
+        ];
+        let config = v2_line_configs(&flags, &[0, 0, 0]).unwrap();
+        assert_eq!(config.flags, flags[0].v2_flags());
+        assert_eq!(config.num_attrs, 1);
+        assert_eq!(config.attrs[0].mask, 0b010);
+    }
+
+    #[test]
+    fn line_configs_limited() {
+        // Distinct input-only flags: the first is the default, ten attributes
+        let inputs: Vec<LineRequestFlags> = (0..32)
+            .map(LineRequestFlags::from_bits_truncate)
+            .filter(|flags| !flags.contains(LineRequestFlags::OUTPUT))
+            .take(ffi::GPIO_V2_LINE_NUM_ATTRS_MAX + 1)
+            .collect();
+        let values = vec![0; inputs.len()];
+        let config = v2_line_configs(&inputs, &values).unwrap();
+        assert_eq!(config.num_attrs as usize, ffi::GPIO_V2_LINE_NUM_ATTRS_MAX);
+
+        // An output needs an attribute for its flags and one for its value
+        let mut flags = inputs;
+        flags.push(LineRequestFlags::OUTPUT);
+        let values = vec![0; flags.len()];
+        match v2_line_configs(&flags, &values) {
+            Err(e) => assert_eq!(
+                e.kind(),
+                &ErrorKind::TooManyConfigs(ffi::GPIO_V2_LINE_NUM_ATTRS_MAX + 2)
+            ),
+            Ok(_) => panic!("too many configurations accepted"),
+        }
+    }
+
+    #[test]
+    fn port_bits_lsb_first() {
+        assert_eq!(
+            map_port_bits(0b1010_0110, BitOrder::LsbFirst, 8),
+            0b1010_0110
+        );
+        assert_eq!(map_port_bits(0b0110, BitOrder::LsbFirst, 4), 0b0110);
+    }
+
+    #[test]
+    fn port_bits_msb_first() {
+        // Line 0 carries bit 7, line 7 bit 0
+        assert_eq!(
+            map_port_bits(0b1000_0000, BitOrder::MsbFirst, 8),
+            0b0000_0001
+        );
+        assert_eq!(
+            map_port_bits(0b1010_0110, BitOrder::MsbFirst, 8),
+            0b0110_0101
+        );
+        // On a 4 line port, line 0 carries bit 3
+        assert_eq!(map_port_bits(0b1000, BitOrder::MsbFirst, 4), 0b0001);
+        assert_eq!(map_port_bits(0b0011, BitOrder::MsbFirst, 4), 0b1100);
+        assert_eq!(map_port_bits(0b1, BitOrder::MsbFirst, 1), 0b1);
+    }
+
+    #[test]
+    fn port_bits_round_trip() {
+        for width in 1..=8 {
+            for byte in 0..(1u16 << width) {
+                let byte = byte as u8;
+                for order in [BitOrder::LsbFirst, BitOrder::MsbFirst] {
+                    let lines = map_port_bits(byte, order, width);
+                    assert!(u16::from(lines) < 1 << width);
+                    assert_eq!(map_port_bits(lines, order, width), byte);
+                }
+            }
+        }
+    }
+
+    fn raw_event(id: u32) -> RawEvent {
+        RawEvent {
+            timestamp: 1000,
+            id,
+            seqno: Some(1),
+            line_seqno: Some(1),
+            offset: Some(3),
+        }
+    }
+
+    #[test]
+    fn known_event_ids_checked() {
+        let event = raw_event(ffi::GPIOEVENT_EVENT_RISING_EDGE)
+            .checked()
+            .unwrap();
+        assert_eq!(event.event_type(), EventType::RisingEdge);
+        assert_eq!(event.raw_id(), ffi::GPIOEVENT_EVENT_RISING_EDGE);
+        assert_eq!(event.offset(), Some(3));
+        let event = raw_event(ffi::GPIOEVENT_EVENT_FALLING_EDGE)
+            .checked()
+            .unwrap();
+        assert_eq!(event.event_type(), EventType::FallingEdge);
+    }
+
+    #[test]
+    fn unknown_event_id_rejected() {
+        match raw_event(7).checked() {
+            Err(e) => assert_eq!(e.kind(), &ErrorKind::UnknownEvent(7)),
+            Ok(event) => panic!("unexpected event {:?}", event),
+        }
+    }
+}
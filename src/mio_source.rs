@@ -0,0 +1,84 @@
+// Copyright (c) 2018 The rust-gpio-cdev Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Integration with the `mio` event loop.
+
+use mio::event::Source;
+use mio::unix::SourceFd;
+use mio::{Interest, Registry, Token};
+
+use std::io;
+use std::os::unix::io::AsRawFd;
+
+use super::LineEventHandle;
+
+/// Allows registering a `LineEventHandle` with a `mio::Poll`
+///
+/// The handle becomes readable when an event is queued.  As `mio` is edge
+/// triggered, all queued events need to be read after each readiness
+/// notification; put the handle into non-blocking mode with
+/// [`LineEventHandle::set_nonblocking`] first so that reading stops with
+/// `WouldBlock` once the queue is empty instead of blocking.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use gpio_cdev::{Chip, EventRequestFlags, LineRequestFlags};
+/// use mio::{Events, Interest, Poll, Token};
+///
+/// let mut chip = Chip::new("/dev/gpiochip0")?;
+/// let mut handle = chip.get_line(4)?.events(
+///     LineRequestFlags::INPUT,
+///     EventRequestFlags::BOTH_EDGES,
+///     "mio-events",
+/// )?;
+/// handle.set_nonblocking(true)?;
+///
+/// let mut poll = Poll::new()?;
+/// poll.registry()
+///     .register(&mut handle, Token(0), Interest::READABLE)?;
+///
+/// let mut events = Events::with_capacity(8);
+/// loop {
+///     poll.poll(&mut events, None)?;
+///     for event in handle.by_ref() {
+///         match event {
+///             Ok(event) => println!("{:?}", event),
+///             Err(e) if e.raw_os_error() == Some(libc::EAGAIN) => break,
+///             Err(e) => return Err(e.into()),
+///         }
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`LineEventHandle::set_nonblocking`]: struct.LineEventHandle.html#method.set_nonblocking
+impl Source for LineEventHandle {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2018 The rust-gpio-cdev Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! In-memory GPIO lines for testing code without hardware.
+//!
+//! Code which is generic over the [`LineInput`], [`LineOutput`] and
+//! [`LineEvents`] traits can be handed a [`MockLine`] in tests instead of a
+//! real handle.  Clones of a `MockLine` share their state, so a test keeps
+//! one clone to drive inputs, inject events and inspect the values written
+//! through the other.
+//!
+//! # Example
+//!
+//! ```
+//! use gpio_cdev::mock::MockChip;
+//! use gpio_cdev::{EventType, LineEvents, LineOutput};
+//!
+//! fn follow<I: LineEvents, O: LineOutput>(input: &mut I, output: &O) -> Result<(), gpio_cdev::Error> {
+//!     let event = input.get_event()?;
+//!     output.set_value((event.event_type() == EventType::RisingEdge) as u8)
+//! }
+//!
+//! # fn main() -> Result<(), gpio_cdev::Error> {
+//! let chip = MockChip::new(2);
+//! let mut input = chip.line(0)?;
+//! let output = chip.line(1)?;
+//!
+//! input.inject_event(EventType::RisingEdge, 1_000);
+//! follow(&mut input, &output)?;
+//! assert_eq!(output.writes(), vec![1]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`LineInput`]: ../trait.LineInput.html
+//! [`LineOutput`]: ../trait.LineOutput.html
+//! [`LineEvents`]: ../trait.LineEvents.html
+//! [`MockLine`]: struct.MockLine.html
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::errors::{event_err, offset_err, Result};
+use crate::{validate_value, EventType, LineEvent, LineEvents, LineInput, LineOutput};
+
+/// A simulated GPIO chip holding a fixed number of [`MockLine`]s
+///
+/// [`MockLine`]: struct.MockLine.html
+#[derive(Debug, Clone)]
+pub struct MockChip {
+    lines: Vec<MockLine>,
+}
+
+impl MockChip {
+    /// Create a chip with `num_lines` lines, all initially at 0
+    pub fn new(num_lines: u32) -> Self {
+        Self {
+            lines: (0..num_lines).map(|_| MockLine::new(0)).collect(),
+        }
+    }
+
+    /// The number of lines of this chip
+    pub fn num_lines(&self) -> u32 {
+        self.lines.len() as u32
+    }
+
+    /// Get the line at `offset`, sharing its state with the chip
+    pub fn line(&self, offset: u32) -> Result<MockLine> {
+        self.lines
+            .get(offset as usize)
+            .cloned()
+            .ok_or_else(|| offset_err(offset))
+    }
+}
+
+#[derive(Debug)]
+struct MockState {
+    value: u8,
+    writes: Vec<u8>,
+    events: VecDeque<LineEvent>,
+}
+
+/// A simulated GPIO line
+///
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct MockLine {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockLine {
+    /// Create a line with the given initial value
+    pub fn new(value: u8) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState {
+                value,
+                writes: Vec::new(),
+                events: VecDeque::new(),
+            })),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        // A panic while holding the lock cannot leave the state inconsistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Set the value subsequently read from the line, as if driven externally
+    pub fn set_input(&self, value: u8) {
+        self.state().value = value;
+    }
+
+    /// All values written with [`LineOutput::set_value`], oldest first
+    ///
+    /// [`LineOutput::set_value`]: ../trait.LineOutput.html#tymethod.set_value
+    pub fn writes(&self) -> Vec<u8> {
+        self.state().writes.clone()
+    }
+
+    /// Queue an edge event, to be returned by [`LineEvents::get_event`]
+    ///
+    /// The value of the line is updated to match the edge (1 after a rising
+    /// edge, 0 after a falling edge).
+    ///
+    /// [`LineEvents::get_event`]: ../trait.LineEvents.html#tymethod.get_event
+    pub fn inject_event(&self, event_type: EventType, timestamp: u64) {
+        let mut state = self.state();
+        state.value = match event_type {
+            EventType::RisingEdge => 1,
+            EventType::FallingEdge => 0,
+        };
+        state.events.push_back(LineEvent {
+            timestamp,
+            event_type,
+            id: match event_type {
+                EventType::RisingEdge => crate::ffi::GPIOEVENT_EVENT_RISING_EDGE,
+                EventType::FallingEdge => crate::ffi::GPIOEVENT_EVENT_FALLING_EDGE,
+            },
+            seqno: None,
+            line_seqno: None,
+            offset: None,
+        });
+    }
+}
+
+impl LineInput for MockLine {
+    fn get_value(&self) -> Result<u8> {
+        Ok(self.state().value)
+    }
+}
+
+impl LineOutput for MockLine {
+    fn set_value(&self, value: u8) -> Result<()> {
+        validate_value(value)?;
+        let mut state = self.state();
+        state.value = value;
+        state.writes.push(value);
+        Ok(())
+    }
+}
+
+impl LineEvents for MockLine {
+    /// Pop the oldest injected event
+    ///
+    /// Unlike a real event handle this does not block: if no event has been
+    /// injected an [`ErrorKind::Event`] error with `EAGAIN` is returned.
+    ///
+    /// [`ErrorKind::Event`]: ../errors/enum.ErrorKind.html#variant.Event
+    fn get_event(&mut self) -> Result<LineEvent> {
+        self.state()
+            .events
+            .pop_front()
+            .ok_or_else(|| event_err(nix::errno::Errno::EAGAIN))
+    }
+}